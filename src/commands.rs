@@ -1,13 +1,42 @@
+use crate::default_error::DefaultError;
+use crate::pipelines::Pipeline;
+use crate::tag_template::TagTemplate;
+use crate::{config, git_service};
 use clap::{Parser, Subcommand};
+use compare_command::CompareCommandArgs;
+use current_command::CurrentCommandArgs;
+use explain_command::ExplainCommandArgs;
+use flow_command::FlowCommandArgs;
+use lint_commits_command::LintCommitsCommandArgs;
+use list_command::ListCommandArgs;
+use next_command::NextCommandArgs;
+use promote_command::PromoteCommandArgs;
+use prune_command::PruneCommandArgs;
 use release_command::ReleaseCommandArgs;
+use satisfies_command::SatisfiesCommandArgs;
 use scope_command::ScopeCommandArgs;
+use std::env;
 use std::error::Error;
 use tag_command::TagCommandArgs;
+use validate_command::ValidateCommandArgs;
 use version_command::VersionCommandArgs;
 
+mod breaking_changes;
+mod compare_command;
+mod current_command;
+mod explain_command;
+mod flow_command;
+mod lint_commits_command;
+mod list_command;
+mod next_command;
+mod preflight;
+mod promote_command;
+mod prune_command;
 mod release_command;
+mod satisfies_command;
 mod scope_command;
 mod tag_command;
+mod validate_command;
 mod version_command;
 
 #[derive(Parser)]
@@ -16,6 +45,14 @@ mod version_command;
 struct Cli {
     #[command(subcommand)]
     pub(crate) command: Commands,
+
+    /// Branch to version on a plain git repo, e.g. when HEAD is detached
+    #[arg(long, global = true, env = "SVCI_BRANCH")]
+    branch: Option<String>,
+
+    /// Template of version tag names, e.g. release-{major}.{minor}.{patch}{pre}{build}
+    #[arg(long, global = true, env = "TAG_TEMPLATE")]
+    tag_template: Option<String>,
 }
 
 #[derive(Subcommand)]
@@ -24,13 +61,118 @@ enum Commands {
     Scope(ScopeCommandArgs),
     Tag(TagCommandArgs),
     Release(ReleaseCommandArgs),
+    /// Print the current version of the branch
+    Current(CurrentCommandArgs),
+    /// List version tags sorted by precedence
+    List(ListCommandArgs),
+    /// Print the next version for a scope, without branch rules
+    Next(NextCommandArgs),
+    /// Compare two versions by precedence
+    Compare(CompareCommandArgs),
+    /// Check a version against the versioning scheme
+    Validate(ValidateCommandArgs),
+    /// Print the versions that satisfy a version requirement
+    Satisfies(SatisfiesCommandArgs),
+    /// Tag the commit of a pre-release with its official version
+    Promote(PromoteCommandArgs),
+    /// Start and finish git-flow release and hotfix branches
+    Flow(FlowCommandArgs),
+    /// Print how the version command derives the upcoming and last version
+    Explain(ExplainCommandArgs),
+    /// Delete old pre-release tags and their forge releases
+    Prune(PruneCommandArgs),
+    /// Check commit messages against Conventional Commits
+    LintCommits(LintCommitsCommandArgs),
 }
 
 pub(crate) fn run() -> Result<(), Box<dyn Error>> {
-    match Cli::parse().command {
+    let cli = Cli::parse();
+    if let Some(branch) = &cli.branch {
+        env::set_var("SVCI_BRANCH", branch);
+    }
+    if let Some(tag_template) = &cli.tag_template {
+        env::set_var("TAG_TEMPLATE", tag_template);
+    }
+
+    match cli.command {
         Commands::Version(args) => version_command::run(args),
         Commands::Scope(args) => scope_command::run(args),
         Commands::Tag(args) => tag_command::run(args),
         Commands::Release(args) => release_command::run(args),
+        Commands::Current(args) => current_command::run(args),
+        Commands::List(args) => list_command::run(args),
+        Commands::Next(args) => next_command::run(args),
+        Commands::Compare(args) => compare_command::run(args),
+        Commands::Validate(args) => validate_command::run(args),
+        Commands::Satisfies(args) => satisfies_command::run(args),
+        Commands::Promote(args) => promote_command::run(args),
+        Commands::Flow(args) => flow_command::run(args),
+        Commands::Explain(args) => explain_command::run(args),
+        Commands::Prune(args) => prune_command::run(args),
+        Commands::LintCommits(args) => lint_commits_command::run(args),
     }
 }
+
+/// Tag names of the repository, fetched from `remotes` first if the pipeline forces it
+fn tag_names(pipeline: &dyn Pipeline, remotes: &[String]) -> Result<Vec<String>, Box<dyn Error>> {
+    pipeline.init();
+
+    git_service::tag_names(
+        &config::clone_target_path(),
+        pipeline.force_fetch_tags(),
+        remotes,
+        &pipeline.git_username(),
+        &pipeline.git_token(),
+    )
+    .map_err(|e| {
+        Box::new(DefaultError {
+            message: "Failed to retrieve tags".to_string(),
+            source: Some(Box::new(e)),
+        }) as Box<dyn Error>
+    })
+}
+
+/// `tag_name` named after TAG_TEMPLATE if it is set and `tag_name` is a version,
+/// e.g. v1.2.3 as release-1.2.3 with `release-{major}.{minor}.{patch}{pre}{build}`
+fn templated_tag_name(tag_name: &str) -> Result<String, DefaultError> {
+    if env::var("TAG_TEMPLATE").is_err() {
+        return Ok(tag_name.to_string());
+    }
+
+    Ok(config::default_tag_template()?
+        .render_version_string(tag_name)
+        .unwrap_or(tag_name.to_string()))
+}
+
+/// Fails if the release of `version`, a tag name of `template`, is outside the requirement of a
+/// release guard of `branch`
+fn check_release_guards(
+    branch: &str,
+    version: &str,
+    template: &TagTemplate,
+) -> Result<(), Box<dyn Error>> {
+    for (pattern, req) in config::release_guards()? {
+        if !pattern.is_match(branch) {
+            continue;
+        }
+
+        let release = template
+            .parse(version)
+            .ok_or_else(|| DefaultError {
+                message: format!("{} is not a semantic version", version),
+                source: None,
+            })?
+            .release();
+        if !req.matches(&release) {
+            return Err(Box::new(DefaultError {
+                message: format!(
+                    "Release guard of branch {} (\"{}\") does not allow {}",
+                    branch, pattern, version
+                ),
+                source: None,
+            }));
+        }
+    }
+
+    Ok(())
+}
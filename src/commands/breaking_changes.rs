@@ -0,0 +1,110 @@
+use crate::conventional_commit::ConventionalCommit;
+use crate::default_error::DefaultError;
+use crate::explanation::Explanation;
+use crate::semantic_version::SemanticVersion;
+use crate::{config, git_service};
+use clap::Args;
+use std::error::Error;
+
+const OFF: &str = "off";
+const WARN: &str = "warn";
+const FAIL: &str = "fail";
+const ESCALATE: &str = "escalate";
+
+const SCOPES: [&str; 3] = ["patch", "minor", "major"];
+
+#[derive(Args)]
+pub(crate) struct BreakingChangesArgs {
+    /// What to do when commits since the last official tag have breaking changes (`!` or a BREAKING CHANGE footer) but the scope is below major, or below minor while the major version is 0
+    #[arg(
+        long,
+        env,
+        default_value = OFF,
+        value_parser = [OFF, WARN, FAIL, ESCALATE]
+    )]
+    breaking_changes: String,
+}
+
+impl BreakingChangesArgs {
+    pub(crate) fn is_enabled(&self) -> bool {
+        self.breaking_changes != OFF
+    }
+}
+
+/// `scope`, or the scope breaking changes since `last_official_tag_name` need when escalating.
+/// Only patch, minor and major scopes are checked
+pub(crate) fn checked_scope(
+    args: &BreakingChangesArgs,
+    scope: &str,
+    last_official_tag_name: Option<&str>,
+    last_official_version: &SemanticVersion,
+    explanation: &mut Explanation,
+) -> Result<String, Box<dyn Error>> {
+    let rank = |scope: &str| SCOPES.iter().position(|s| *s == scope);
+    // Breaking changes bump minor before 1.0.0
+    let required = match last_official_version.major {
+        0 => "minor",
+        _ => "major",
+    };
+    if !args.is_enabled() || rank(scope).is_none() || rank(scope) >= rank(required) {
+        return Ok(scope.to_string());
+    }
+
+    // The tag may only be a forge release or an image tag
+    if let Some(tag_name) = last_official_tag_name
+        .filter(|tag_name| !git_service::has_tag(&config::clone_target_path(), tag_name))
+    {
+        let skipped = format!(
+            "{} is not a git tag, so the commits since it are unknown. Skipping the check",
+            tag_name
+        );
+        log::warn!("Breaking changes: {}", skipped);
+        explanation.add("breaking changes", skipped);
+        return Ok(scope.to_string());
+    }
+
+    let range = match last_official_tag_name {
+        Some(tag_name) => format!("refs/tags/{}..HEAD", tag_name),
+        None => "HEAD".to_string(),
+    };
+    let breaking: Vec<String> = git_service::commit_messages(&config::clone_target_path(), &range)?
+        .into_iter()
+        .filter(|(_, message)| ConventionalCommit::parse(message).is_ok_and(|c| c.breaking))
+        .map(|(sha, message)| {
+            format!(
+                "{} {}",
+                &sha[..8],
+                message.lines().next().unwrap_or_default()
+            )
+        })
+        .collect();
+    if breaking.is_empty() {
+        return Ok(scope.to_string());
+    }
+
+    let message = format!(
+        "{} breaking changes since {} need a {} bump, but the scope is {}",
+        breaking.len(),
+        last_official_tag_name.unwrap_or("the first commit"),
+        required,
+        scope
+    );
+    explanation.add(
+        "breaking changes",
+        format!("{}: {}", message, breaking.join(", ")),
+    );
+    match args.breaking_changes.as_str() {
+        FAIL => Err(Box::new(DefaultError {
+            message: format!("{}:\n  - {}", message, breaking.join("\n  - ")),
+            source: None,
+        })),
+        ESCALATE => {
+            eprintln!("{}. Bumping {}", message, required);
+            Ok(required.to_string())
+        }
+        _ => {
+            log::warn!("{}:\n  - {}", message, breaking.join("\n  - "));
+            Ok(scope.to_string())
+        }
+    }
+}
@@ -0,0 +1,46 @@
+use crate::default_error::DefaultError;
+use crate::semantic_version::SemanticVersion;
+use clap::Args;
+use std::cmp::Ordering;
+use std::error::Error;
+use std::process;
+
+#[derive(Args)]
+pub(crate) struct CompareCommandArgs {
+    a: String,
+    b: String,
+
+    /// Exit with 0 if a = b, 1 if a < b and 2 if a > b instead of printing the result
+    #[arg(long)]
+    exit_code: bool,
+}
+
+pub(crate) fn run(args: CompareCommandArgs) -> Result<(), Box<dyn Error>> {
+    let a = parse(args.a)?;
+    let b = parse(args.b)?;
+    let ordering = a.cmp(&b);
+
+    if args.exit_code {
+        process::exit(match ordering {
+            Ordering::Equal => 0,
+            Ordering::Less => 1,
+            Ordering::Greater => 2,
+        });
+    }
+
+    let symbol = match ordering {
+        Ordering::Less => "<",
+        Ordering::Equal => "=",
+        Ordering::Greater => ">",
+    };
+    println!("{}", symbol);
+
+    Ok(())
+}
+
+fn parse(version: String) -> Result<SemanticVersion, DefaultError> {
+    SemanticVersion::from_string(version).map_err(|e| DefaultError {
+        message: e,
+        source: None,
+    })
+}
@@ -0,0 +1,75 @@
+use crate::commands::version_command::prerelease_stage;
+use crate::semantic_version::SemanticVersion;
+use crate::version_scheme::SemVer;
+use crate::{commands, config, git_service, pipelines};
+use clap::Args;
+use std::error::Error;
+
+#[derive(Args)]
+pub(crate) struct CurrentCommandArgs {
+    /// Latest official version, even on a pre-release branch
+    #[arg(long)]
+    official_only: bool,
+
+    /// Scope of the branch's upcoming version. Pre-releases of other version lines don't count
+    #[arg(short, long, env, default_value = "minor")]
+    scope: String,
+
+    /// Remote to fetch tags from. Can be repeated
+    #[arg(
+        long = "remote",
+        env = "REMOTES",
+        value_delimiter = ',',
+        default_value = "origin"
+    )]
+    remotes: Vec<String>,
+}
+
+pub(crate) fn run(args: CurrentCommandArgs) -> Result<(), Box<dyn Error>> {
+    let pipeline = pipelines::current_pipeline();
+    let tag_names = commands::tag_names(pipeline, &args.remotes)?;
+    let template = config::default_tag_template()?;
+
+    let tags = git_service::tags_by_template(&tag_names, &template);
+    let last_official = tags
+        .iter()
+        .filter(|(_, version)| version.prerelease_stage.is_empty())
+        .last()
+        .cloned();
+    let mut last_official_version = last_official
+        .as_ref()
+        .map_or_else(SemanticVersion::default, |(_, version)| version.clone());
+
+    // On develop, feature/*, release/* and hotfix/*, pre-releases of the branch's stage
+    // and upcoming version count too
+    let stage = match args.official_only {
+        true => "".to_string(),
+        false => prerelease_stage(&pipeline.branch_name()),
+    };
+    let upcoming = match args.scope.as_str() {
+        "major" | "minor" | "patch" => Some(last_official_version.increase_by_scope(args.scope)),
+        _ => None,
+    };
+    let current = match stage.is_empty() {
+        true => last_official,
+        false => tags
+            .into_iter()
+            .filter(|(_, version)| {
+                version.prerelease_stage == stage
+                    && upcoming.as_ref().map_or(true, |upcoming| {
+                        (version.major, version.minor, version.patch)
+                            == (upcoming.major, upcoming.minor, upcoming.patch)
+                    })
+            })
+            .chain(last_official)
+            .max_by(|(_, a), (_, b)| a.cmp(b)),
+    };
+
+    // The matched tag name, as version tags of other formats may match the template too
+    match current {
+        Some((tag_name, _)) => println!("{}", tag_name),
+        None => println!("{}", template.render(&SemanticVersion::default(), &SemVer)),
+    }
+
+    Ok(())
+}
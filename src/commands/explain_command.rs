@@ -0,0 +1,44 @@
+use crate::commands::version_command::{self, VersionArgs};
+use crate::explanation::Explanation;
+use clap::Args;
+use serde_json::json;
+use std::error::Error;
+
+const TEXT: &str = "text";
+const JSON: &str = "json";
+
+#[derive(Args)]
+pub(crate) struct ExplainCommandArgs {
+    #[command(flatten)]
+    version: VersionArgs,
+
+    #[arg(long, default_value = TEXT, value_parser = [TEXT, JSON])]
+    format: String,
+}
+
+pub(crate) fn run(args: ExplainCommandArgs) -> Result<(), Box<dyn Error>> {
+    let mut explanation = Explanation::default();
+    let versions = version_command::versions(&args.version, &mut explanation);
+
+    if args.format == JSON {
+        let (upcoming_version, last_version, error) = match &versions {
+            Ok((upcoming, last)) => (json!(upcoming), json!(last), json!(null)),
+            Err(e) => (json!(null), json!(null), json!(e.to_string())),
+        };
+        let output = json!({
+            "steps": explanation.to_json(),
+            "upcoming_version": upcoming_version,
+            "last_version": last_version,
+            "error": error,
+        });
+        println!("{}", serde_json::to_string_pretty(&output)?);
+    } else {
+        print!("{}", explanation.to_text());
+        if let Ok((upcoming_version, last_version)) = &versions {
+            println!("UPCOMING_VERSION={}", upcoming_version);
+            println!("LAST_VERSION={}", last_version);
+        }
+    }
+
+    versions.map(|_| ())
+}
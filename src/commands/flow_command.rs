@@ -0,0 +1,338 @@
+use crate::default_error::DefaultError;
+use crate::pipelines::PipelineInfo;
+use crate::semantic_version::SemanticVersion;
+use crate::version_scheme::SemVer;
+use crate::{commands, config, git_service, pipelines};
+use clap::{Args, Subcommand};
+use git2::{BranchType, Repository};
+use regex::Regex;
+use std::error::Error;
+
+const RELEASE_BRANCH_PATTERN: &str = r"^release/([0-9]+)\.([0-9]+|x)\.x$";
+const HOTFIX_BRANCH_PATTERN: &str = r"^hotfix/([0-9]+)\.([0-9]+)\.([0-9]+)$";
+
+#[derive(Args)]
+pub(crate) struct FlowCommandArgs {
+    #[command(subcommand)]
+    branch_type: FlowBranchType,
+
+    /// Main branch. Defaults to main, or master if there is no main
+    #[arg(long, global = true, env = "MAIN_BRANCH")]
+    main_branch: Option<String>,
+
+    #[arg(long, global = true, env = "DEVELOP_BRANCH", default_value = "develop")]
+    develop_branch: String,
+
+    #[arg(long, global = true, env = "TAG_PREFIX", default_value = "v")]
+    tag_prefix: String,
+
+    /// Push the created branch, or the merged branches and the tag
+    #[arg(long, global = true, action)]
+    push: bool,
+
+    /// Remote to fetch tags from and push to. Can be repeated
+    #[arg(
+        long = "remote",
+        global = true,
+        env = "REMOTES",
+        value_delimiter = ',',
+        default_value = "origin"
+    )]
+    remotes: Vec<String>,
+}
+
+#[derive(Subcommand)]
+enum FlowBranchType {
+    /// release/<major>.<minor>.x branches, or release/<major>.x.x for a major release
+    Release {
+        #[command(subcommand)]
+        action: FlowAction,
+    },
+    /// hotfix/<major>.<minor>.<patch> branches
+    Hotfix {
+        #[command(subcommand)]
+        action: FlowAction,
+    },
+}
+
+#[derive(Subcommand)]
+enum FlowAction {
+    /// Create and check out the branch of the next version
+    Start {
+        /// Start a major release (release branches only)
+        #[arg(long, action)]
+        major: bool,
+
+        /// Branch to start from. Defaults to the develop branch for releases and the main branch for hotfixes
+        #[arg(long)]
+        base: Option<String>,
+    },
+    /// Merge the checked out branch into the main branch, tag the merge and merge it back into the develop branch
+    Finish {
+        #[arg(long, env, default_value = "")]
+        tag_message: String,
+    },
+}
+
+pub(crate) fn run(args: FlowCommandArgs) -> Result<(), Box<dyn Error>> {
+    let pipeline = pipelines::current_pipeline();
+    let tag_names = commands::tag_names(pipeline, &args.remotes)?;
+    let pipeline_info = pipeline.info();
+    let repo = Repository::open(config::clone_target_path())?;
+    let remote = &args.remotes[0];
+    let main_branch = args
+        .main_branch
+        .clone()
+        .unwrap_or_else(|| default_main_branch(&repo, remote));
+
+    let (is_release, action) = match args.branch_type {
+        FlowBranchType::Release { action } => (true, action),
+        FlowBranchType::Hotfix { action } => (false, action),
+    };
+    match action {
+        FlowAction::Start { major, base } => {
+            if major && !is_release {
+                return Err(flow_error(
+                    "--major is only for release branches".to_string(),
+                ));
+            }
+            let mut last_official_tag = git_service::last_tag_by_template(
+                &tag_names,
+                &config::tag_template(&args.tag_prefix)?,
+                |version| version.prerelease_stage.is_empty(),
+                Some(SemanticVersion::default()),
+            )
+            .unwrap();
+            let scope = match (is_release, major) {
+                (true, true) => "major",
+                (true, false) => "minor",
+                (false, _) => "patch",
+            };
+            let next = last_official_tag.increase_by_scope(scope.to_string());
+            let (branch_name, base) = match scope {
+                "major" => (
+                    format!("release/{}.x.x", next.major),
+                    base.unwrap_or(args.develop_branch),
+                ),
+                "minor" => (
+                    format!("release/{}.{}.x", next.major, next.minor),
+                    base.unwrap_or(args.develop_branch),
+                ),
+                _ => (
+                    format!("hotfix/{}", next.to_string(false)),
+                    base.unwrap_or(main_branch),
+                ),
+            };
+
+            start(&repo, &branch_name, &base, remote)?;
+            if args.push {
+                push_refs(
+                    &repo,
+                    &pipeline_info,
+                    &[format!("refs/heads/{}", branch_name)],
+                    &args.remotes,
+                )?;
+            }
+            println!("{}", branch_name);
+        }
+        FlowAction::Finish { tag_message } => {
+            let branch_name = pipeline_info.branch_name.clone();
+            let version = branch_version(&branch_name, is_release)?;
+            let tag_name = config::tag_template(&args.tag_prefix)?.render(&version, &SemVer);
+            if tag_names.iter().any(|name| name == &tag_name) {
+                return Err(flow_error(format!("Tag {} already exists", tag_name)));
+            }
+
+            let targets = [main_branch, args.develop_branch];
+            let ref_names = finish(
+                &repo,
+                &pipeline_info,
+                &branch_name,
+                &targets,
+                &tag_name,
+                &tag_message,
+                remote,
+            )?;
+            if args.push {
+                push_refs(&repo, &pipeline_info, &ref_names, &args.remotes)?;
+            }
+            println!("{}", tag_name);
+        }
+    }
+
+    Ok(())
+}
+
+fn start(
+    repo: &Repository,
+    branch_name: &str,
+    base: &str,
+    remote: &str,
+) -> Result<(), Box<dyn Error>> {
+    if repo.find_branch(branch_name, BranchType::Local).is_ok() {
+        return Err(flow_error(format!("Branch {} already exists", branch_name)));
+    }
+
+    let base_commit = git_service::local_branch(repo, base, remote)
+        .and_then(|branch| branch.get().peel_to_commit())
+        .map_err(|e| DefaultError {
+            message: format!("Branch {} not found", base),
+            source: Some(Box::new(e)),
+        })?;
+    git_service::create_branch(repo, branch_name, base_commit.id())?;
+    eprintln!("start {} from {}", branch_name, base);
+
+    Ok(())
+}
+
+/// Merges `branch_name` into each target and tags the merge into the first. Returns the refs to push
+fn finish(
+    repo: &Repository,
+    pipeline_info: &PipelineInfo,
+    branch_name: &str,
+    targets: &[String],
+    tag_name: &str,
+    tag_message: &str,
+    remote: &str,
+) -> Result<Vec<String>, Box<dyn Error>> {
+    let branch_commit = repo
+        .find_branch(branch_name, BranchType::Local)?
+        .get()
+        .peel_to_commit()?;
+    let message = format!("Merge branch '{}'", branch_name);
+
+    // Every merge is checked first, so a conflict leaves no merge or tag behind
+    for target in targets {
+        git_service::local_branch(repo, target, remote).map_err(|e| DefaultError {
+            message: format!("Branch {} not found", target),
+            source: Some(Box::new(e)),
+        })?;
+        if git_service::has_merge_conflicts(repo, target, branch_commit.id())? {
+            return Err(flow_error(format!(
+                "Merging {} into {} has conflicts. Nothing was merged",
+                branch_name, target
+            )));
+        }
+    }
+
+    let mut ref_names = vec![];
+    for (i, target) in targets.iter().enumerate() {
+        let merge_id = git_service::merge_no_ff(
+            repo,
+            target,
+            branch_commit.id(),
+            &format!("{} into {}", message, target),
+            &pipeline_info.git_username,
+            &pipeline_info.git_email,
+        )?;
+        eprintln!("merge {} into {}", branch_name, target);
+
+        // The release is the merge into the main branch
+        if i == 0 {
+            git_service::tag_commit(
+                repo,
+                tag_name,
+                merge_id,
+                tag_message,
+                &pipeline_info.git_username,
+                &pipeline_info.git_email,
+            )?;
+        }
+        ref_names.push(format!("refs/heads/{}", target));
+    }
+    ref_names.push(format!("refs/tags/{}", tag_name));
+
+    Ok(ref_names)
+}
+
+/// Official version a release or hotfix branch name stands for
+fn branch_version(branch_name: &str, is_release: bool) -> Result<SemanticVersion, Box<dyn Error>> {
+    let (pattern, branch_type, example) = match is_release {
+        true => (RELEASE_BRANCH_PATTERN, "release", "release/1.2.x"),
+        false => (HOTFIX_BRANCH_PATTERN, "hotfix", "hotfix/1.2.3"),
+    };
+    let captures = Regex::new(pattern)
+        .unwrap()
+        .captures(branch_name)
+        .ok_or_else(|| {
+            flow_error(format!(
+                "{} is not a {} branch, e.g. {}",
+                branch_name, branch_type, example
+            ))
+        })?;
+    let part = |i: usize| {
+        captures
+            .get(i)
+            .and_then(|part| part.as_str().parse::<u64>().ok())
+            .unwrap_or(0)
+    };
+
+    Ok(SemanticVersion {
+        major: part(1),
+        minor: part(2),
+        patch: part(3),
+        ..SemanticVersion::default()
+    })
+}
+
+fn default_main_branch(repo: &Repository, remote: &str) -> String {
+    ["main", "master"]
+        .into_iter()
+        .find(|name| {
+            repo.find_branch(name, BranchType::Local).is_ok()
+                || repo
+                    .find_branch(&format!("{}/{}", remote, name), BranchType::Remote)
+                    .is_ok()
+        })
+        .unwrap_or("main")
+        .to_string()
+}
+
+fn push_refs(
+    repo: &Repository,
+    pipeline_info: &PipelineInfo,
+    ref_names: &[String],
+    remotes: &[String],
+) -> Result<(), Box<dyn Error>> {
+    let names = ref_names
+        .iter()
+        .map(|name| {
+            name.trim_start_matches("refs/heads/")
+                .trim_start_matches("refs/tags/")
+        })
+        .collect::<Vec<&str>>()
+        .join(", ");
+
+    let mut failed_remotes = vec![];
+    for remote in remotes {
+        match git_service::push_refs(
+            repo,
+            remote,
+            &pipeline_info.git_username,
+            &pipeline_info.git_token,
+            ref_names,
+        ) {
+            Ok(()) => println!("{}: pushed {}", remote, names),
+            Err(e) => {
+                println!("{}: failed to push {} ({})", remote, names, e.message());
+                failed_remotes.push(remote.as_str());
+            }
+        }
+    }
+
+    if failed_remotes.is_empty() {
+        Ok(())
+    } else {
+        Err(flow_error(format!(
+            "Failed to push to {}",
+            failed_remotes.join(", ")
+        )))
+    }
+}
+
+fn flow_error(message: String) -> Box<dyn Error> {
+    Box::new(DefaultError {
+        message,
+        source: None,
+    })
+}
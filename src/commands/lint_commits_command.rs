@@ -0,0 +1,196 @@
+use crate::conventional_commit::ConventionalCommit;
+use crate::default_error::DefaultError;
+use crate::{commands, config, git_service, pipelines};
+use clap::Args;
+use git2::Repository;
+use std::error::Error;
+use std::fs;
+
+const LINT_FAILED_EXIT_CODE: i32 = 1;
+
+// Line of `git commit --verbose` above the diff. It and everything below aren't part of the message
+const SCISSORS_LINE: &str = "# ------------------------ >8 ------------------------";
+
+// Messages git generates, and fixups that are squashed before merging
+const SKIPPED_PREFIXES: [&str; 3] = ["Merge ", "fixup! ", "squash! "];
+
+#[derive(Args)]
+pub(crate) struct LintCommitsCommandArgs {
+    /// Revision range to lint, e.g. v1.2.3..HEAD. Defaults to the commits of the pull request,
+    /// or the commits since the last version tag
+    #[arg(conflicts_with = "message_file")]
+    range: Option<String>,
+
+    /// Lint a single message file instead, e.g. in a commit-msg hook
+    #[arg(long)]
+    message_file: Option<String>,
+
+    /// Allowed commit types
+    #[arg(
+        long = "type",
+        env = "COMMIT_TYPES",
+        value_delimiter = ',',
+        default_value = "build,chore,ci,docs,feat,fix,perf,refactor,revert,style,test"
+    )]
+    types: Vec<String>,
+
+    /// Allowed commit scopes. Any scope is allowed if empty
+    #[arg(long = "scope", env = "COMMIT_SCOPES", value_delimiter = ',')]
+    scopes: Vec<String>,
+
+    #[arg(long, env, default_value_t = 100)]
+    max_header_length: usize,
+
+    /// Footers every commit must have, e.g. Signed-off-by
+    #[arg(
+        long = "required-footer",
+        env = "REQUIRED_FOOTERS",
+        value_delimiter = ','
+    )]
+    required_footers: Vec<String>,
+
+    /// Remote to fetch tags from. Can be repeated. The pull request's target branch is fetched
+    /// from the first
+    #[arg(
+        long = "remote",
+        env = "REMOTES",
+        value_delimiter = ',',
+        default_value = "origin"
+    )]
+    remotes: Vec<String>,
+}
+
+pub(crate) fn run(args: LintCommitsCommandArgs) -> Result<(), Box<dyn Error>> {
+    let messages = match &args.message_file {
+        Some(path) => {
+            let message = fs::read_to_string(path).map_err(|e| DefaultError {
+                message: format!("Failed to read the message file: {}", path),
+                source: Some(Box::new(e)),
+            })?;
+            let message: Vec<&str> = message
+                .lines()
+                .take_while(|line| *line != SCISSORS_LINE)
+                .collect();
+            let message = message.join("\n");
+            vec![("".to_string(), message)]
+        }
+        None => {
+            let range = match &args.range {
+                Some(range) => range.clone(),
+                None => default_range(&args)?,
+            };
+            eprintln!("lint {}", range);
+            git_service::commit_messages(&config::clone_target_path(), &range)?
+        }
+    };
+
+    let mut linted = 0;
+    let mut failed = 0;
+    for (sha, message) in &messages {
+        if SKIPPED_PREFIXES.iter().any(|p| message.starts_with(p)) {
+            continue;
+        }
+        linted += 1;
+
+        let violations = violations(&args, message);
+        if violations.is_empty() {
+            continue;
+        }
+        failed += 1;
+        let header = message.lines().next().unwrap_or_default();
+        match sha.is_empty() {
+            true => eprintln!("{}", header),
+            false => eprintln!("{} {}", &sha[..8], header),
+        }
+        for violation in violations {
+            eprintln!("  - {}", violation);
+        }
+    }
+
+    if failed > 0 {
+        eprintln!(
+            "{} of {} commits do not follow Conventional Commits",
+            failed, linted
+        );
+        std::process::exit(LINT_FAILED_EXIT_CODE);
+    }
+    eprintln!("{} commits follow Conventional Commits", linted);
+
+    Ok(())
+}
+
+/// Commits of the pull request, or since the last version tag reachable from HEAD
+fn default_range(args: &LintCommitsCommandArgs) -> Result<String, Box<dyn Error>> {
+    let pipeline = pipelines::current_pipeline();
+    let tag_names = commands::tag_names(pipeline, &args.remotes)?;
+
+    if let Some(pull_request) = pipeline.pull_request() {
+        let target_ref = format!(
+            "refs/remotes/{}/{}",
+            args.remotes[0], pull_request.target_branch
+        );
+        let repo = Repository::open(config::clone_target_path())?;
+        git_service::fetch_refs(
+            &repo,
+            &args.remotes[0],
+            &pipeline.git_username(),
+            &pipeline.git_token(),
+            &[&format!(
+                "refs/heads/{}:{}",
+                pull_request.target_branch, target_ref
+            )],
+        )?;
+        return Ok(format!("{}..HEAD", target_ref));
+    }
+
+    let version_tags: Vec<String> =
+        git_service::tags_by_template(&tag_names, &config::default_tag_template()?)
+            .into_iter()
+            .map(|(tag_name, _)| tag_name)
+            .collect();
+    let range = match git_service::last_reachable_tag(&config::clone_target_path(), &version_tags)?
+    {
+        Some(tag_name) => format!("refs/tags/{}..HEAD", tag_name),
+        None => "HEAD".to_string(),
+    };
+
+    Ok(range)
+}
+
+fn violations(args: &LintCommitsCommandArgs, message: &str) -> Vec<String> {
+    let commit = match ConventionalCommit::parse(message) {
+        Ok(commit) => commit,
+        Err(e) => return vec![e],
+    };
+    let mut violations = vec![];
+
+    if !args.types.contains(&commit.commit_type) {
+        violations.push(format!(
+            "type {} is not one of {}",
+            commit.commit_type,
+            args.types.join(", ")
+        ));
+    }
+    if let Some(scope) = &commit.scope {
+        if !args.scopes.is_empty() && !args.scopes.contains(scope) {
+            violations.push(format!(
+                "scope {} is not one of {}",
+                scope,
+                args.scopes.join(", ")
+            ));
+        }
+    }
+    if commit.header.chars().count() > args.max_header_length {
+        violations.push(format!(
+            "header is longer than {} characters",
+            args.max_header_length
+        ));
+    }
+    for footer in &args.required_footers {
+        if !commit.footers.iter().any(|(token, _)| token == footer) {
+            violations.push(format!("footer {} is missing", footer));
+        }
+    }
+
+    violations
+}
@@ -0,0 +1,66 @@
+use crate::default_error::DefaultError;
+use crate::semantic_version::SemanticVersion;
+use crate::{commands, config, git_service, pipelines};
+use clap::Args;
+use std::error::Error;
+
+#[derive(Args)]
+pub(crate) struct ListCommandArgs {
+    /// Only official versions
+    #[arg(long, conflicts_with = "stage")]
+    official_only: bool,
+
+    /// Only pre-releases of this stage, e.g. rc
+    #[arg(long)]
+    stage: Option<String>,
+
+    /// Only versions newer than this one
+    #[arg(long)]
+    since: Option<String>,
+
+    /// Remote to fetch tags from. Can be repeated
+    #[arg(
+        long = "remote",
+        env = "REMOTES",
+        value_delimiter = ',',
+        default_value = "origin"
+    )]
+    remotes: Vec<String>,
+}
+
+pub(crate) fn run(args: ListCommandArgs) -> Result<(), Box<dyn Error>> {
+    let since = args
+        .since
+        .map(SemanticVersion::from_string)
+        .transpose()
+        .map_err(|e| DefaultError {
+            message: format!("Invalid --since version: {}", e),
+            source: None,
+        })?;
+
+    let pipeline = pipelines::current_pipeline();
+    let tag_names = commands::tag_names(pipeline, &args.remotes)?;
+
+    for (tag_name, version) in
+        git_service::tags_by_template(&tag_names, &config::default_tag_template()?)
+    {
+        if args.official_only && !version.prerelease_stage.is_empty() {
+            continue;
+        }
+        if let Some(stage) = &args.stage {
+            let stage_prefix = format!("{}.", stage);
+            if &version.prerelease_stage != stage
+                && !version.prerelease_stage.starts_with(&stage_prefix)
+            {
+                continue;
+            }
+        }
+        if since.as_ref().is_some_and(|since| &version <= since) {
+            continue;
+        }
+
+        println!("{}", tag_name);
+    }
+
+    Ok(())
+}
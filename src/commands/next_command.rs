@@ -0,0 +1,76 @@
+use crate::commands::version_command::SchemeArgs;
+use crate::default_error::DefaultError;
+use crate::semantic_version::SemanticVersion;
+use crate::{commands, config, git_service, pipelines};
+use clap::Args;
+use std::error::Error;
+
+#[derive(Args)]
+pub(crate) struct NextCommandArgs {
+    #[arg(short, long, default_value = "minor", value_parser = ["major", "minor", "patch"])]
+    scope: String,
+
+    /// Pre-release stage, e.g. rc. Numbered after the existing pre-releases of the next version
+    #[arg(long)]
+    stage: Option<String>,
+
+    /// Version to increase instead of the latest official version
+    #[arg(long)]
+    from: Option<String>,
+
+    /// Remote to fetch tags from. Can be repeated
+    #[arg(
+        long = "remote",
+        env = "REMOTES",
+        value_delimiter = ',',
+        default_value = "origin"
+    )]
+    remotes: Vec<String>,
+
+    #[command(flatten)]
+    scheme: SchemeArgs,
+}
+
+pub(crate) fn run(args: NextCommandArgs) -> Result<(), Box<dyn Error>> {
+    let pipeline = pipelines::current_pipeline();
+    let tag_names = commands::tag_names(pipeline, &args.remotes)?;
+    let template = config::default_tag_template()?;
+    let scheme = args.scheme.version_scheme()?;
+
+    let base = match args.from {
+        Some(from) => SemanticVersion::from_string(from)
+            .map_err(|e| DefaultError {
+                message: format!("Invalid --from version: {}", e),
+                source: None,
+            })?
+            .release(),
+        None => git_service::last_tag_by_template(
+            &tag_names,
+            &template,
+            |version| version.prerelease_stage.is_empty(),
+            Some(SemanticVersion::default()),
+        )
+        .unwrap(),
+    };
+    let mut next = scheme.increase(&base, &args.scope);
+
+    if let Some(stage) = args.stage {
+        let last_number = git_service::tags_by_template(&tag_names, &template)
+            .into_iter()
+            .map(|(_, version)| version)
+            .filter(|version| {
+                (version.major, version.minor, version.patch)
+                    == (next.major, next.minor, next.patch)
+                    && version.prerelease_stage == stage
+            })
+            .map(|version| version.prerelease_number)
+            .max()
+            .unwrap_or(0);
+        next.prerelease_stage = stage;
+        next.prerelease_number = last_number + 1;
+    }
+
+    println!("{}", template.render(&next, scheme.as_ref()));
+
+    Ok(())
+}
@@ -0,0 +1,191 @@
+use crate::pipelines::Pipeline;
+use crate::{config, git_service};
+use clap::Args;
+use git2::{Oid, Repository};
+use regex::Regex;
+use std::error::Error;
+
+/// Exit code when a pre-flight check failed
+pub(crate) const PREFLIGHT_FAILED_EXIT_CODE: i32 = 3;
+
+const TAG: &str = "tag";
+const VERSION: &str = "version";
+const BRANCH: &str = "branch";
+const CLEAN: &str = "clean";
+const PUSHED: &str = "pushed";
+const CI: &str = "ci";
+
+#[derive(Args)]
+pub(crate) struct PreflightArgs {
+    /// Check to run before any write: tag (doesn't exist locally or on a remote), version (greater than the last official), branch (allowed), clean (working tree), pushed (HEAD is on the remote branch) or ci (no failed forge checks of HEAD). Can be repeated
+    #[arg(
+        long = "check",
+        env = "RELEASE_CHECKS",
+        value_delimiter = ',',
+        value_parser = [TAG, VERSION, BRANCH, CLEAN, PUSHED, CI]
+    )]
+    checks: Vec<String>,
+
+    /// Branches the branch check allows
+    #[arg(long, env, default_value = r"^(main|master|release/.*|hotfix/.*)$")]
+    allowed_branches: String,
+}
+
+/// Runs the checks of `args` for tagging HEAD as `tag_name`. Exits with
+/// PREFLIGHT_FAILED_EXIT_CODE after listing every failed check. For a `release`,
+/// `tag_name` may already exist when it points at HEAD
+pub(crate) fn run(
+    args: &PreflightArgs,
+    pipeline: &dyn Pipeline,
+    tag_name: &str,
+    remotes: &[String],
+    release: bool,
+) -> Result<(), Box<dyn Error>> {
+    if args.checks.is_empty() {
+        return Ok(());
+    }
+    pipeline.init();
+
+    let mut failures = vec![];
+    for check in &args.checks {
+        match run_check(check, args, pipeline, tag_name, remotes, release) {
+            Ok(check_failures) => failures.extend(check_failures),
+            Err(e) => failures.push(format!("{}: {}", check, e)),
+        }
+    }
+
+    if failures.is_empty() {
+        eprintln!("pre-flight checks passed: {}", args.checks.join(", "));
+        return Ok(());
+    }
+
+    eprintln!("Pre-flight checks failed:");
+    for failure in failures {
+        eprintln!("  - {}", failure);
+    }
+    std::process::exit(PREFLIGHT_FAILED_EXIT_CODE);
+}
+
+fn run_check(
+    check: &str,
+    args: &PreflightArgs,
+    pipeline: &dyn Pipeline,
+    tag_name: &str,
+    remotes: &[String],
+    release: bool,
+) -> Result<Vec<String>, Box<dyn Error>> {
+    let pipeline_info = pipeline.info();
+    let repo = Repository::open(config::clone_target_path())?;
+    let head = repo.head()?.peel_to_commit()?.id();
+    let tag_ref = format!("refs/tags/{}", tag_name);
+    // The tag made by the tag step of this release
+    let tag_of_head = repo
+        .find_reference(&tag_ref)
+        .ok()
+        .filter(|tag| release && tag.peel_to_commit().is_ok_and(|commit| commit.id() == head))
+        .and_then(|tag| tag.target());
+    let remote_refs = |remote: &str| {
+        git_service::remote_refs(
+            &repo,
+            remote,
+            &pipeline_info.git_username,
+            &pipeline_info.git_token,
+        )
+    };
+    let mut failures = vec![];
+
+    match check {
+        TAG => {
+            if tag_of_head.is_none() && repo.find_reference(&tag_ref).is_ok() {
+                failures.push(format!("tag: {} already exists locally", tag_name));
+            }
+            for remote in remotes {
+                if remote_refs(remote)?
+                    .iter()
+                    .any(|(name, oid)| name == &tag_ref && Some(*oid) != tag_of_head)
+                {
+                    failures.push(format!("tag: {} already exists on {}", tag_name, remote));
+                }
+            }
+        }
+        VERSION => {
+            let template = config::default_tag_template()?;
+            let version = template.parse(tag_name).ok_or_else(|| {
+                format!(
+                    "{} does not match the tag template {}",
+                    tag_name,
+                    template.as_str()
+                )
+            })?;
+            let tag_names: Vec<String> = repo
+                .tag_names(None)?
+                .iter()
+                .flatten()
+                .filter(|name| tag_of_head.is_none() || *name != tag_name)
+                .map(str::to_string)
+                .collect();
+            let last_official = git_service::last_tag_by_template(
+                &tag_names,
+                &template,
+                |version| version.prerelease_stage.is_empty(),
+                None,
+            );
+            if let Some(last_official) = last_official.filter(|last| &version <= last) {
+                failures.push(format!(
+                    "version: {} is not greater than the last official version {}",
+                    tag_name,
+                    last_official.to_string(false)
+                ));
+            }
+        }
+        BRANCH => {
+            let allowed_branches = Regex::new(&args.allowed_branches)?;
+            if !allowed_branches.is_match(&pipeline_info.branch_name) {
+                failures.push(format!(
+                    "branch: {} does not match {}",
+                    pipeline_info.branch_name, args.allowed_branches
+                ));
+            }
+        }
+        CLEAN => {
+            let changed_paths = git_service::changed_paths(&repo)?;
+            if !changed_paths.is_empty() {
+                failures.push(format!(
+                    "clean: the working tree has changes in {}",
+                    changed_paths.join(", ")
+                ));
+            }
+        }
+        PUSHED => {
+            let branch_ref = format!("refs/heads/{}", pipeline_info.branch_name);
+            for remote in remotes {
+                let remote_branch = remote_refs(remote)?
+                    .into_iter()
+                    .find(|(name, _)| name == &branch_ref)
+                    .map(|(_, oid)| oid);
+                if !remote_branch.is_some_and(|oid| contains(&repo, oid, head)) {
+                    failures.push(format!(
+                        "pushed: HEAD {} is not pushed to {}/{}",
+                        pipeline_info.short_commit_sha, remote, pipeline_info.branch_name
+                    ));
+                }
+            }
+        }
+        _ => {
+            let failed_checks = pipeline.forge()?.failed_checks(&head.to_string())?;
+            if !failed_checks.is_empty() {
+                failures.push(format!(
+                    "ci: checks of HEAD failed: {}",
+                    failed_checks.join(", ")
+                ));
+            }
+        }
+    }
+
+    Ok(failures)
+}
+
+/// Whether the history of `tip` contains `commit`
+fn contains(repo: &Repository, tip: Oid, commit: Oid) -> bool {
+    tip == commit || repo.graph_descendant_of(tip, commit).unwrap_or(false)
+}
@@ -0,0 +1,124 @@
+use crate::commands::tag_command;
+use crate::default_error::DefaultError;
+use crate::release::Release;
+use crate::{commands, config, git_service, pipelines};
+use clap::Args;
+use git2::Repository;
+use std::error::Error;
+
+#[derive(Args)]
+pub(crate) struct PromoteCommandArgs {
+    /// Pre-release tag to promote, e.g. v1.4.0-rc.3
+    #[arg()]
+    prerelease_tag: String,
+
+    #[arg(long, env, default_value = "")]
+    tag_message: String,
+
+    /// Also create a forge release of the official tag with the description of the pre-release's release
+    #[arg(long, env, action)]
+    create_release: bool,
+
+    /// Remote to fetch tags from and push the tag to. Can be repeated
+    #[arg(
+        long = "remote",
+        env = "REMOTES",
+        value_delimiter = ',',
+        default_value = "origin"
+    )]
+    remotes: Vec<String>,
+}
+
+pub(crate) fn run(args: PromoteCommandArgs) -> Result<(), Box<dyn Error>> {
+    let pipeline = pipelines::current_pipeline();
+    let tag_names = commands::tag_names(pipeline, &args.remotes)?;
+    let pipeline_info = pipeline.info();
+    let template = config::default_tag_template()?;
+
+    let prerelease_tag = args.prerelease_tag.as_str();
+    if !tag_names.iter().any(|name| name == prerelease_tag) {
+        return Err(refused(format!("Tag {} does not exist", prerelease_tag)));
+    }
+    let prerelease = template.parse(prerelease_tag).ok_or_else(|| {
+        refused(format!(
+            "Tag {} is not a semantic version of the tag template {}",
+            prerelease_tag,
+            template.as_str()
+        ))
+    })?;
+    if prerelease.prerelease_stage.is_empty() {
+        return Err(refused(format!(
+            "Tag {} is not a pre-release",
+            prerelease_tag
+        )));
+    }
+
+    // Only the last pre-release of a stage was tested as the release
+    let newer = git_service::tags_by_template(&tag_names, &template)
+        .into_iter()
+        .filter(|(_, version)| {
+            (version.major, version.minor, version.patch)
+                == (prerelease.major, prerelease.minor, prerelease.patch)
+                && version.prerelease_stage == prerelease.prerelease_stage
+                && version > &prerelease
+        })
+        .last();
+    if let Some((newer_tag, _)) = newer {
+        return Err(refused(format!("A newer pre-release {} exists", newer_tag)));
+    }
+
+    let official_tag = template.official_tag_name(prerelease_tag).unwrap();
+    if tag_names.iter().any(|name| name == &official_tag) {
+        return Err(refused(format!("Tag {} already exists", official_tag)));
+    }
+
+    let repo = Repository::open(config::clone_target_path())?;
+    let commit_id = git_service::tagged_commit(&repo, prerelease_tag)?;
+    eprintln!(
+        "promote {} to {} on {}",
+        prerelease_tag, official_tag, commit_id
+    );
+
+    git_service::tag_commit(
+        &repo,
+        &official_tag,
+        commit_id,
+        &args.tag_message,
+        &pipeline_info.git_username,
+        &pipeline_info.git_email,
+    )?;
+    tag_command::push_tag(&repo, &pipeline_info, &official_tag, &args.remotes)?;
+
+    if args.create_release {
+        let forge = pipeline.forge()?;
+        eprintln!("release on {}", forge.name());
+
+        let description = forge
+            .release_description(prerelease_tag)
+            .unwrap_or_else(|e| {
+                log::warn!("Failed to get the release of {}: {}", prerelease_tag, e);
+                "".to_string()
+            });
+        let release = Release {
+            name: official_tag.clone(),
+            description,
+            tag_name: official_tag,
+            tag_message: args.tag_message,
+            generate_release_notes: false,
+            previous_tag: "".to_string(),
+            attachments: vec![],
+        };
+        let parsed = forge.create_release(&release, &commit_id.to_string())?;
+
+        println!("{:#?}", parsed);
+    }
+
+    Ok(())
+}
+
+fn refused(message: String) -> Box<dyn Error> {
+    Box::new(DefaultError {
+        message: format!("Cannot promote: {}", message),
+        source: None,
+    })
+}
@@ -0,0 +1,163 @@
+use crate::default_error::DefaultError;
+use crate::semantic_version::SemanticVersion;
+use crate::{commands, config, git_service, pipelines};
+use clap::Args;
+use git2::Repository;
+use std::collections::{HashMap, HashSet};
+use std::error::Error;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const SECONDS_PER_DAY: i64 = 24 * 60 * 60;
+
+#[derive(Args)]
+pub(crate) struct PruneCommandArgs {
+    /// Keep the last N pre-releases of each version and stage, e.g. of 1.3.0-rc
+    #[arg(long, env = "PRUNE_KEEP")]
+    keep: Option<usize>,
+
+    /// Delete the pre-releases of officially released versions
+    #[arg(long, env = "PRUNE_RELEASED", action)]
+    released: bool,
+
+    /// Skip pre-releases tagged within the last N days
+    #[arg(long, env = "PRUNE_MIN_AGE_DAYS", default_value_t = 0)]
+    min_age_days: i64,
+
+    /// Also delete the forge releases of the pruned tags
+    #[arg(long, env = "PRUNE_DELETE_RELEASES", action)]
+    delete_releases: bool,
+
+    /// Only print the tags that would be deleted
+    #[arg(long, action)]
+    dry_run: bool,
+
+    /// Remote to fetch tags from and delete the tags on. Can be repeated
+    #[arg(
+        long = "remote",
+        env = "REMOTES",
+        value_delimiter = ',',
+        default_value = "origin"
+    )]
+    remotes: Vec<String>,
+}
+
+pub(crate) fn run(args: PruneCommandArgs) -> Result<(), Box<dyn Error>> {
+    if args.keep.is_none() && !args.released {
+        return Err(Box::new(DefaultError {
+            message: "Nothing to prune. Set --keep or --released".to_string(),
+            source: None,
+        }));
+    }
+
+    let pipeline = pipelines::current_pipeline();
+    let tag_names = commands::tag_names(pipeline, &args.remotes)?;
+    let pipeline_info = pipeline.info();
+    let repo = Repository::open(config::clone_target_path())?;
+
+    let tags = git_service::tags_by_template(&tag_names, &config::default_tag_template()?);
+    let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() as i64;
+    let mut pruned = vec![];
+    for tag_name in prunable_tags(&tags, args.keep, args.released) {
+        if now - git_service::tag_time(&repo, &tag_name)? < args.min_age_days * SECONDS_PER_DAY {
+            continue;
+        }
+        pruned.push(tag_name);
+    }
+
+    if args.dry_run {
+        for tag_name in &pruned {
+            println!("would delete {}", tag_name);
+        }
+        return Ok(());
+    }
+    if pruned.is_empty() {
+        eprintln!("nothing to prune");
+        return Ok(());
+    }
+
+    if args.delete_releases {
+        // Releases are deleted by tag, as listing them is paginated
+        let forge = pipeline.forge()?;
+        for tag_name in &pruned {
+            if forge.delete_release(tag_name)? {
+                eprintln!("{}: deleted release {}", forge.name(), tag_name);
+            }
+        }
+    }
+
+    for remote in &args.remotes {
+        let remote_refs = git_service::remote_refs(
+            &repo,
+            remote,
+            &pipeline_info.git_username,
+            &pipeline_info.git_token,
+        )?;
+        let ref_names: Vec<String> = pruned
+            .iter()
+            .map(|tag_name| format!("refs/tags/{}", tag_name))
+            .filter(|tag_ref| remote_refs.iter().any(|(name, _)| name == tag_ref))
+            .map(|tag_ref| format!(":{}", tag_ref))
+            .collect();
+        if ref_names.is_empty() {
+            continue;
+        }
+
+        git_service::push_refs(
+            &repo,
+            remote,
+            &pipeline_info.git_username,
+            &pipeline_info.git_token,
+            &ref_names,
+        )
+        .map_err(|e| {
+            Box::new(DefaultError {
+                message: format!("Failed to delete tags on {}", remote),
+                source: Some(Box::new(e)),
+            })
+        })?;
+        eprintln!("{}: deleted {} tags", remote, ref_names.len());
+    }
+
+    for tag_name in &pruned {
+        repo.tag_delete(tag_name)?;
+        println!("{}", tag_name);
+    }
+
+    Ok(())
+}
+
+/// Pre-release tags beyond the last `keep` of their version and stage, and with `released`,
+/// those of versions with an official tag. `tags` are sorted lowest first
+fn prunable_tags(
+    tags: &[(String, SemanticVersion)],
+    keep: Option<usize>,
+    released: bool,
+) -> Vec<String> {
+    let core = |v: &SemanticVersion| (v.major, v.minor, v.patch);
+    let officials: HashSet<_> = tags
+        .iter()
+        .filter(|(_, v)| v.prerelease_stage.is_empty())
+        .map(|(_, v)| core(v))
+        .collect();
+
+    let mut newer_count: HashMap<_, usize> = HashMap::new();
+    let mut prunable = vec![];
+    for (tag_name, version) in tags.iter().rev() {
+        if version.prerelease_stage.is_empty() {
+            continue;
+        }
+        let count = newer_count
+            .entry((core(version), version.prerelease_stage.as_str()))
+            .or_default();
+        *count += 1;
+
+        let is_released = released && officials.contains(&core(version));
+        let is_old = keep.is_some_and(|keep| *count > keep);
+        if is_released || is_old {
+            prunable.push(tag_name.clone());
+        }
+    }
+    prunable.reverse();
+
+    prunable
+}
@@ -1,5 +1,6 @@
-use crate::pipelines;
+use crate::commands::preflight::{self, PreflightArgs};
 use crate::release::Release;
+use crate::{commands, pipelines};
 use clap::Args;
 use std::error::Error;
 
@@ -32,10 +33,26 @@ pub(crate) struct ReleaseCommandArgs {
     /// ex) v0.1.0 => 0.1.0
     #[arg(short, long, env, action)]
     strip_prefix_v: bool,
+
+    /// (Only for Gitea Actions) file to upload as a release attachment. Can be repeated.
+    #[arg(short, long = "attachment", env = "ATTACHMENTS", value_delimiter = ',')]
+    attachments: Vec<String>,
+
+    /// Remote the tag and pushed checks look at. Can be repeated
+    #[arg(
+        long = "remote",
+        env = "REMOTES",
+        value_delimiter = ',',
+        default_value = "origin"
+    )]
+    remotes: Vec<String>,
+
+    #[command(flatten)]
+    preflight: PreflightArgs,
 }
 
 pub(crate) fn run(args: ReleaseCommandArgs) -> Result<(), Box<dyn Error>> {
-    let tag_name = args.tag_name.unwrap_or(args.name.clone());
+    let tag_name = commands::templated_tag_name(args.tag_name.as_ref().unwrap_or(&args.name))?;
     let release = Release {
         name: args.name,
         description: args.description,
@@ -43,9 +60,17 @@ pub(crate) fn run(args: ReleaseCommandArgs) -> Result<(), Box<dyn Error>> {
         tag_message: args.tag_message,
         generate_release_notes: args.generate_release_notes,
         previous_tag: args.previous_tag,
+        attachments: args.attachments,
     };
 
     let pipeline = pipelines::current_pipeline();
+    preflight::run(
+        &args.preflight,
+        pipeline,
+        &release.tag_name,
+        &args.remotes,
+        true,
+    )?;
     let parsed = pipeline.create_release(&release)?;
 
     println!("{:#?}", parsed);
@@ -0,0 +1,42 @@
+use crate::default_error::DefaultError;
+use crate::semantic_version::{SemanticVersion, VersionReq};
+use clap::Args;
+use std::error::Error;
+use std::process;
+
+#[derive(Args)]
+pub(crate) struct SatisfiesCommandArgs {
+    /// Version requirement, e.g. "^1.2", ">=1.2.0 <2" or "1.x || ~2.1"
+    requirement: String,
+
+    /// Versions to check
+    #[arg(required = true)]
+    versions: Vec<String>,
+}
+
+pub(crate) fn run(args: SatisfiesCommandArgs) -> Result<(), Box<dyn Error>> {
+    let req = VersionReq::parse(&args.requirement).map_err(|e| DefaultError {
+        message: e,
+        source: None,
+    })?;
+
+    let mut satisfied = false;
+    for version_string in args.versions {
+        let version =
+            SemanticVersion::from_string(version_string.clone()).map_err(|e| DefaultError {
+                message: e,
+                source: None,
+            })?;
+        if req.matches(&version) {
+            println!("{}", version_string);
+            satisfied = true;
+        }
+    }
+
+    // Exit code 1 when no version satisfies the requirement, for use in shell conditions
+    if !satisfied {
+        process::exit(1);
+    }
+
+    Ok(())
+}
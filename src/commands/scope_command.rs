@@ -1,5 +1,8 @@
+use crate::commands::breaking_changes::{self, BreakingChangesArgs};
 use crate::default_error::DefaultError;
-use crate::pipelines;
+use crate::explanation::Explanation;
+use crate::semantic_version::SemanticVersion;
+use crate::{commands, config, git_service, pipelines};
 use clap::Args;
 use regex::Regex;
 use std::error::Error;
@@ -21,6 +24,9 @@ pub(crate) struct ScopeCommandArgs {
 
     #[arg(long, env, default_value = r"^(main|master)$")]
     release: String,
+
+    #[command(flatten)]
+    breaking_changes: BreakingChangesArgs,
 }
 
 pub(crate) fn run(args: ScopeCommandArgs) -> Result<(), Box<dyn Error>> {
@@ -30,22 +36,53 @@ pub(crate) fn run(args: ScopeCommandArgs) -> Result<(), Box<dyn Error>> {
     let release_regex = Regex::new(&args.release)?;
 
     let pipeline = pipelines::current_pipeline();
+    // A tag pipeline builds the tagged version as is
+    if let Some(tag_name) = pipeline.tag_name() {
+        eprintln!("tag {}", tag_name);
+        println!("release");
+        return Ok(());
+    }
     let branch_name = &pipeline.branch_name();
 
-    if major_regex.is_match(branch_name) {
-        println!("major")
+    let scope = if major_regex.is_match(branch_name) {
+        "major"
     } else if minor_regex.is_match(branch_name) {
-        println!("minor")
+        "minor"
     } else if patch_regex.is_match(branch_name) {
-        println!("patch")
+        "patch"
     } else if release_regex.is_match(branch_name) {
-        println!("release")
+        "release"
     } else {
         return Err(Box::new(DefaultError {
             message: format!("Unknown branch name: {}", branch_name),
             source: None,
         }));
+    };
+
+    if !args.breaking_changes.is_enabled() {
+        println!("{}", scope);
+        return Ok(());
     }
 
+    // Breaking changes are checked against the tags of the clone
+    let tag_names = commands::tag_names(pipeline, &[])?;
+    let (last_official_tag_name, last_official_version) =
+        match git_service::tags_by_template(&tag_names, &config::default_tag_template()?)
+            .into_iter()
+            .filter(|(_, version)| version.prerelease_stage.is_empty())
+            .last()
+        {
+            Some((tag_name, version)) => (Some(tag_name), version),
+            None => (None, SemanticVersion::default()),
+        };
+    let scope = breaking_changes::checked_scope(
+        &args.breaking_changes,
+        scope,
+        last_official_tag_name.as_deref(),
+        &last_official_version,
+        &mut Explanation::default(),
+    )?;
+    println!("{}", scope);
+
     Ok(())
 }
@@ -1,6 +1,7 @@
+use crate::commands::preflight::{self, PreflightArgs};
 use crate::default_error::DefaultError;
 use crate::pipelines::PipelineInfo;
-use crate::{config, git_service, pipelines};
+use crate::{commands, config, git_service, pipelines};
 use clap::Args;
 use git2::Repository;
 use std::error::Error;
@@ -13,6 +14,17 @@ pub(crate) struct TagCommandArgs {
     tag_message: String,
     #[arg(short, long, env, action)]
     strip_prefix_v: bool,
+    /// Remote to push the tag to. Can be repeated
+    #[arg(
+        long = "remote",
+        env = "REMOTES",
+        value_delimiter = ',',
+        default_value = "origin"
+    )]
+    remotes: Vec<String>,
+
+    #[command(flatten)]
+    preflight: PreflightArgs,
 }
 
 pub(crate) fn run(args: TagCommandArgs) -> Result<(), Box<dyn Error>> {
@@ -20,7 +32,8 @@ pub(crate) fn run(args: TagCommandArgs) -> Result<(), Box<dyn Error>> {
     pipeline.init();
     let pipeline_info = pipeline.info();
 
-    let mut tag_name = args.tag_name.as_str();
+    let templated_tag_name = commands::templated_tag_name(&args.tag_name)?;
+    let mut tag_name = templated_tag_name.as_str();
     if args.strip_prefix_v {
         if let Some(stripped) = tag_name.strip_prefix('v') {
             tag_name = stripped
@@ -29,13 +42,21 @@ pub(crate) fn run(args: TagCommandArgs) -> Result<(), Box<dyn Error>> {
 
     let tag_message = args.tag_message.as_str();
 
-    tag_and_push(&pipeline_info, tag_name, tag_message)
+    commands::check_release_guards(
+        &pipeline_info.branch_name,
+        tag_name,
+        &config::default_tag_template()?,
+    )?;
+    preflight::run(&args.preflight, pipeline, tag_name, &args.remotes, false)?;
+
+    tag_and_push(&pipeline_info, tag_name, tag_message, &args.remotes)
 }
 
 fn tag_and_push(
     pipeline_info: &PipelineInfo,
     tag_name: &str,
     tag_message: &str,
+    remotes: &[String],
 ) -> Result<(), Box<dyn Error>> {
     let repo = Repository::open(config::clone_target_path())?;
 
@@ -47,16 +68,39 @@ fn tag_and_push(
         &pipeline_info.git_email,
     )?;
 
-    Ok(git_service::push_tag(
-        &repo,
-        &pipeline_info.git_username,
-        &pipeline_info.git_token,
-        tag_name,
-    )
-    .map_err(|e| {
-        Box::new(DefaultError {
-            message: "Failed to push tag".to_string(),
-            source: Some(Box::new(e)),
-        })
-    })?)
+    push_tag(&repo, pipeline_info, tag_name, remotes)
+}
+
+/// Pushes `tag_name` to every remote, failing after all were tried if any push failed
+pub(crate) fn push_tag(
+    repo: &Repository,
+    pipeline_info: &PipelineInfo,
+    tag_name: &str,
+    remotes: &[String],
+) -> Result<(), Box<dyn Error>> {
+    let mut failed_remotes = vec![];
+    for remote in remotes {
+        match git_service::push_tag(
+            repo,
+            remote,
+            &pipeline_info.git_username,
+            &pipeline_info.git_token,
+            tag_name,
+        ) {
+            Ok(()) => println!("{}: pushed {}", remote, tag_name),
+            Err(e) => {
+                println!("{}: failed to push {} ({})", remote, tag_name, e.message());
+                failed_remotes.push(remote.as_str());
+            }
+        }
+    }
+
+    if failed_remotes.is_empty() {
+        Ok(())
+    } else {
+        Err(Box::new(DefaultError {
+            message: format!("Failed to push tag to {}", failed_remotes.join(", ")),
+            source: None,
+        }))
+    }
 }
@@ -0,0 +1,111 @@
+use crate::default_error::DefaultError;
+use crate::semantic_version::{SemanticVersion, VersionReq, PULL_REQUEST_STAGE};
+use clap::Args;
+use std::error::Error;
+
+#[derive(Args)]
+pub(crate) struct ValidateCommandArgs {
+    #[arg(value_name = "VERSION")]
+    version_string: String,
+
+    /// Prefix of version tags
+    #[arg(long, env = "TAG_PREFIX", default_value = "v")]
+    tag_prefix: String,
+
+    /// Allowed pre-release stages
+    #[arg(
+        long,
+        env = "PRERELEASE_STAGES",
+        value_delimiter = ',',
+        default_value = "dev,rc,pr"
+    )]
+    stages: Vec<String>,
+
+    /// Version requirement the version must satisfy, e.g. ^1.2
+    #[arg(long)]
+    satisfies: Option<String>,
+}
+
+pub(crate) fn run(args: ValidateCommandArgs) -> Result<(), Box<dyn Error>> {
+    let unprefixed = args
+        .version_string
+        .strip_prefix(args.tag_prefix.as_str())
+        .ok_or_else(|| {
+            invalid(
+                &args.version_string,
+                format!("missing the tag prefix \"{}\"", args.tag_prefix),
+            )
+        })?;
+    let version = SemanticVersion::from_string(unprefixed.to_string())
+        .map_err(|e| invalid(&args.version_string, e))?;
+
+    validate_scheme(&version, &args.stages).map_err(|e| invalid(&args.version_string, e))?;
+
+    // Rejects leading zeros, a missing pre-release number and other non-canonical forms
+    let canonical = format!("{}{}", args.tag_prefix, version.to_string(false));
+    if canonical != args.version_string {
+        return Err(invalid(&args.version_string, format!("expected {}", canonical)).into());
+    }
+
+    if let Some(satisfies) = args.satisfies {
+        let req = VersionReq::parse(&satisfies).map_err(|e| DefaultError {
+            message: e,
+            source: None,
+        })?;
+        if !req.matches(&version) {
+            return Err(invalid(
+                &args.version_string,
+                format!("does not satisfy {}", satisfies),
+            )
+            .into());
+        }
+    }
+
+    println!("{}", args.version_string);
+
+    Ok(())
+}
+
+fn validate_scheme(version: &SemanticVersion, stages: &[String]) -> Result<(), String> {
+    let stage = version.prerelease_stage.as_str();
+    if stage.is_empty() {
+        return Ok(());
+    }
+
+    let base_stage = match stage.strip_prefix(PULL_REQUEST_STAGE) {
+        Some(number) if number.starts_with('.') => PULL_REQUEST_STAGE,
+        _ => stage,
+    };
+    if !stages.iter().any(|allowed| allowed == base_stage) {
+        return Err(format!(
+            "pre-release stage {} is not one of {}",
+            base_stage,
+            stages.join(", ")
+        ));
+    }
+
+    // rc versions are published without a commit SHA, other pre-releases end with one
+    let sha = version.commit_short_sha.as_str();
+    match (base_stage, sha.is_empty()) {
+        ("rc", false) => Err(format!(
+            "{} versions must not have a commit SHA",
+            base_stage
+        )),
+        ("rc", true) => Ok(()),
+        (_, true) => Err(format!(
+            "{} versions must end with a commit SHA",
+            base_stage
+        )),
+        (_, false) if !sha.chars().all(|c| c.is_ascii_hexdigit()) => {
+            Err(format!("commit SHA {} is not hexadecimal", sha))
+        }
+        (_, false) => Ok(()),
+    }
+}
+
+fn invalid(version: &str, reason: String) -> DefaultError {
+    DefaultError {
+        message: format!("{} is not a valid version: {}", version, reason),
+        source: None,
+    }
+}
@@ -1,92 +1,839 @@
+use crate::commands::breaking_changes::{self, BreakingChangesArgs};
+use crate::commands::preflight::{self, PreflightArgs};
+use crate::commands::tag_command;
 use crate::default_error::DefaultError;
-use crate::pipelines;
-use crate::semantic_version::SemanticVersion;
-use crate::{config, git_service};
-use clap::Args;
-use git2::string_array::StringArray;
+use crate::explanation::Explanation;
+use crate::pipelines::{Pipeline, PipelineInfo};
+use crate::semantic_version::{SemanticVersion, PULL_REQUEST_STAGE};
+use crate::tag_template::TagTemplate;
+use crate::version_scheme::{CalVer, SemVer, VersionScheme, CALVER, SEMVER};
+use crate::version_sources::{ForgeReleases, GitTags, OciRegistry, VersionSource, FORGE, GIT, OCI};
+use crate::{commands, config, git_service, pipelines, version_scheme, version_sources};
+use chrono::NaiveDate;
+use clap::{ArgAction, Args};
+use git2::Repository;
 use regex::Regex;
 use std::cmp::Ordering;
 use std::error::Error;
 
 const DEV_PATTERN: &str = r"^(develop|feature/.*)$";
 const RELEASE_CANDIDATE_PATTERN: &str = r"^(release|hotfix)/.*$";
-const SEMANTIC_VERSION_TAG_OFFICIAL_PATTERN: &str = r"^v?([0-9]+\.[0-9]+\.[0-9]+)$";
-const SEMANTIC_VERSION_TAG_PRERELEASE_PATTERN: &str = r"^v?([0-9]+\.[0-9]+\.[0-9]+-.+)$";
+const DEV_STAGE: &str = "dev";
+const TAGS: &str = "tags";
+const COMMITS: &str = "commits";
 
 #[derive(Args)]
 pub(crate) struct VersionCommandArgs {
+    #[command(flatten)]
+    version: VersionArgs,
+
+    /// Tag HEAD with the upcoming version
+    #[arg(long, action)]
+    tag: bool,
+
+    /// Push the tag to each remote. Retries with the next pre-release if a concurrent pipeline pushed the tag first
+    #[arg(long, action, requires = "tag")]
+    push: bool,
+
+    #[arg(long, env, default_value = "")]
+    tag_message: String,
+
+    /// Number of times to recompute the version after the pushed tag was taken
+    #[arg(long, env, default_value_t = 3)]
+    tag_retries: u32,
+
+    #[command(flatten)]
+    preflight: PreflightArgs,
+}
+
+/// Options of computing the versions, shared with `svci explain`
+#[derive(Args)]
+pub(crate) struct VersionArgs {
     #[arg(short, long, env, default_value = "minor")]
     scope: String,
+
+    #[command(flatten)]
+    breaking_changes: BreakingChangesArgs,
+
+    /// Remote to fetch tags from. Can be repeated
+    #[arg(
+        long = "remote",
+        env = "REMOTES",
+        value_delimiter = ',',
+        default_value = "origin"
+    )]
+    remotes: Vec<String>,
+
+    /// Maximum number of commits to fetch into a shallow clone while looking for a version tag. 0 disables deepening
+    #[arg(long, env, default_value_t = 1000)]
+    max_fetch_depth: i32,
+
+    /// Version pull/merge requests as `pr.<number>` pre-releases of the target branch's upcoming version
+    #[arg(long, env, default_value_t = true, action = ArgAction::Set)]
+    pull_request_versions: bool,
+
+    /// Prefix of version tags, the `{prefix}` of the tag template. Checked on tag pipelines
+    #[arg(long, env, default_value = "v")]
+    tag_prefix: String,
+
+    /// Number dev pre-releases after the existing dev tags, or by the commits since the last official tag
+    #[arg(long, env, default_value = TAGS, value_parser = [TAGS, COMMITS])]
+    dev_numbering: String,
+
+    /// Where existing versions are found: git tags, forge releases or tags of an OCI image repository. Can be repeated
+    #[arg(
+        long = "version-source",
+        env = "VERSION_SOURCES",
+        value_delimiter = ',',
+        default_value = GIT,
+        value_parser = [GIT, FORGE, OCI]
+    )]
+    version_sources: Vec<String>,
+
+    /// Image repository of the oci version source, e.g. registry.example.com/owner/app. OCI_TOKEN is sent as a bearer token
+    #[arg(long, env)]
+    oci_repository: Option<String>,
+
+    #[command(flatten)]
+    scheme: SchemeArgs,
+
+    /// First official version while there is no official tag, e.g. 1.0.0. Defaults to a bump of 0.0.0
+    #[arg(long, env)]
+    initial_version: Option<String>,
+
+    /// While the major version is 0, bump minor for the major scope and patch for the minor scope
+    #[arg(long = "pre-1-0", env = "PRE_1_0", action)]
+    pre_1_0: bool,
+
+    /// Bump a 0.x version to 1.0.0
+    #[arg(long, action)]
+    graduate: bool,
+}
+
+/// Options of the version scheme, shared with `svci next`
+#[derive(Args)]
+pub(crate) struct SchemeArgs {
+    /// Version scheme
+    #[arg(long, env = "VERSION_SCHEME", default_value = SEMVER, value_parser = [SEMVER, CALVER])]
+    scheme: String,
+
+    /// Format of calendar versions: two of YYYY, YY, 0Y, MM, 0M, WW, 0W, DD, 0D and then MICRO
+    #[arg(long, env, default_value = "YYYY.0M.MICRO")]
+    calver_format: String,
+
+    /// Date of calendar versions, e.g. 2024-06-15. Defaults to the commit date
+    #[arg(long, env = "VERSION_DATE")]
+    date: Option<String>,
+}
+
+impl SchemeArgs {
+    /// SemVer, or CalVer of `--date` or the commit date
+    pub(crate) fn version_scheme(&self) -> Result<Box<dyn VersionScheme>, Box<dyn Error>> {
+        if self.scheme != CALVER {
+            return Ok(Box::new(SemVer));
+        }
+
+        let date = match &self.date {
+            Some(date) => {
+                NaiveDate::parse_from_str(date, "%Y-%m-%d").map_err(|e| DefaultError {
+                    message: format!("Invalid --date: {}", date),
+                    source: Some(Box::new(e)),
+                })?
+            }
+            None => {
+                let (seconds, offset_minutes) =
+                    git_service::commit_time(&config::clone_target_path())?;
+                version_scheme::date_of(seconds, offset_minutes)
+            }
+        };
+        let calver = CalVer::new(&self.calver_format, date).map_err(|e| DefaultError {
+            message: e,
+            source: None,
+        })?;
+
+        Ok(Box::new(calver))
+    }
 }
 
 pub(crate) fn run(args: VersionCommandArgs) -> Result<(), Box<dyn Error>> {
-    // Pipeline
+    let (upcoming_version, last_version) = match args.tag {
+        true => tag_version(&args)?,
+        false => versions(&args.version, &mut Explanation::default())?,
+    };
+
+    println!("UPCOMING_VERSION={}", upcoming_version);
+    println!("LAST_VERSION={}", last_version);
+
+    Ok(())
+}
+
+/// Versions, after tagging HEAD with the upcoming version. If the push is rejected because
+/// the tag was pushed meanwhile, tags are fetched again and the versions recomputed
+fn tag_version(args: &VersionCommandArgs) -> Result<(String, String), Box<dyn Error>> {
+    let pipeline = pipelines::current_pipeline();
+    pipeline.init();
+    let pipeline_info = pipeline.info();
+    if let Some(tag_name) = &pipeline_info.tag_name {
+        return Err(Box::new(DefaultError {
+            message: format!("Cannot tag on the pipeline of tag {}", tag_name),
+            source: None,
+        }));
+    }
+    let repo = Repository::open(config::clone_target_path())?;
+    let remotes = &args.version.remotes;
+    let template = config::tag_template(&args.version.tag_prefix)?;
+    let scheme = args.version.scheme.version_scheme()?;
+
+    let mut retries = 0;
+    loop {
+        let (upcoming_version, last_version) = versions_of(
+            &args.version,
+            pipeline,
+            &pipeline_info,
+            &mut Explanation::default(),
+        )?;
+        preflight::run(&args.preflight, pipeline, &upcoming_version, remotes, false)?;
+        git_service::tag(
+            &repo,
+            &upcoming_version,
+            &args.tag_message,
+            &pipeline_info.git_username,
+            &pipeline_info.git_email,
+        )?;
+        eprintln!("tagged {}", upcoming_version);
+        if !args.push {
+            return Ok((upcoming_version, last_version));
+        }
+
+        let version = template.parse(&upcoming_version);
+        let is_official = version
+            .as_ref()
+            .is_some_and(|version| version.prerelease_stage.is_empty());
+        // Dev and pull request tags of different commits differ by the sha, so the remote
+        // doesn't reject a taken number. It is checked before pushing instead
+        let reserved = match &version {
+            Some(version) if !version.commit_short_sha.is_empty() => {
+                let mut reserved = version.clone();
+                reserved.commit_short_sha.clear();
+                template.render(&reserved, scheme.as_ref())
+            }
+            _ => upcoming_version.clone(),
+        };
+        let is_taken = || {
+            is_version_taken(
+                &repo,
+                &remotes[0],
+                &pipeline_info,
+                &template,
+                &upcoming_version,
+            )
+        };
+        let pushed = match reserved != upcoming_version && is_taken() {
+            true => Err(git2::Error::from_str(&format!("{} is taken", reserved))),
+            false => git_service::push_tag(
+                &repo,
+                &remotes[0],
+                &pipeline_info.git_username,
+                &pipeline_info.git_token,
+                &upcoming_version,
+            ),
+        };
+        match pushed {
+            Ok(()) => {
+                eprintln!("{}: pushed {}", remotes[0], upcoming_version);
+                tag_command::push_tag(&repo, &pipeline_info, &upcoming_version, &remotes[1..])?;
+                return Ok((upcoming_version, last_version));
+            }
+            // Only pre-releases move on to the next number. An official version pushed
+            // meanwhile is released already
+            Err(_) if is_official && is_taken() => {
+                repo.tag_delete(&upcoming_version)?;
+                return Err(Box::new(DefaultError {
+                    message: format!("{} is already released on {}", upcoming_version, remotes[0]),
+                    source: None,
+                }));
+            }
+            Err(e) if retries < args.tag_retries && is_taken() => {
+                retries += 1;
+                eprintln!(
+                    "{}: {} was pushed meanwhile. Retrying ({}/{})",
+                    remotes[0], reserved, retries, args.tag_retries
+                );
+                log::info!("Push rejected: {}", e);
+                repo.tag_delete(&upcoming_version)?;
+                for remote in remotes {
+                    git_service::fetch_refs(
+                        &repo,
+                        remote,
+                        &pipeline_info.git_username,
+                        &pipeline_info.git_token,
+                        &["refs/tags/*:refs/tags/*"],
+                    )?;
+                }
+            }
+            Err(e) => {
+                repo.tag_delete(&upcoming_version)?;
+                return Err(Box::new(DefaultError {
+                    message: format!("Failed to push tag to {}", remotes[0]),
+                    source: Some(Box::new(e)),
+                }));
+            }
+        }
+    }
+}
+
+/// Whether `remote` has `tag_name` already, or a tag of the same version of another commit
+fn is_version_taken(
+    repo: &Repository,
+    remote: &str,
+    pipeline_info: &PipelineInfo,
+    template: &TagTemplate,
+    tag_name: &str,
+) -> bool {
+    let version = template.parse(tag_name);
+
+    git_service::remote_refs(
+        repo,
+        remote,
+        &pipeline_info.git_username,
+        &pipeline_info.git_token,
+    )
+    .is_ok_and(|refs| {
+        refs.iter()
+            .filter_map(|(name, _)| name.strip_prefix("refs/tags/"))
+            .any(|other| {
+                other == tag_name
+                    || template
+                        .parse(other)
+                        .zip(version.as_ref())
+                        .is_some_and(|(other, version)| other.cmp(version) == Ordering::Equal)
+            })
+    })
+}
+
+/// Upcoming and last version, with the steps that derived them added to `explanation`
+pub(crate) fn versions(
+    args: &VersionArgs,
+    explanation: &mut Explanation,
+) -> Result<(String, String), Box<dyn Error>> {
     let pipeline = pipelines::current_pipeline();
     pipeline.init();
     let pipeline_info = pipeline.info();
 
+    versions_of(args, pipeline, &pipeline_info, explanation)
+}
+
+/// Upcoming and last version of the initialized `pipeline`
+fn versions_of(
+    args: &VersionArgs,
+    pipeline: &dyn Pipeline,
+    pipeline_info: &PipelineInfo,
+    explanation: &mut Explanation,
+) -> Result<(String, String), Box<dyn Error>> {
+    // Pipeline
+    explanation.add("pipeline", pipeline.name());
+    explanation.add("branch", &pipeline_info.branch_name);
+
+    let scheme = args.scheme.version_scheme()?;
+    let template = config::tag_template(&args.tag_prefix)?;
+    explanation.add("scheme", &args.scheme.scheme);
+    explanation.add("tag template", template.as_str());
+
     // Tag names
-    let tag_names = git_service::tag_names(
+    let sources = version_sources(args, pipeline, pipeline_info)?;
+    for source in &sources {
+        explanation.add("version source", source.name());
+    }
+    let tag_names = version_sources::merged_tag_names(&sources)?;
+    explain_tags(&tag_names, &template, explanation);
+
+    // Tag pipeline
+    if let Some(tag_name) = &pipeline_info.tag_name {
+        explanation.add(
+            "tag pipeline",
+            format!("{} is the upcoming version", tag_name),
+        );
+        return tag_versions(
+            &tag_names,
+            tag_name,
+            &args.tag_prefix,
+            &template,
+            scheme.as_ref(),
+            explanation,
+        );
+    }
+
+    // Shallow clone
+    git_service::deepen_until_tag_reachable(
         &config::clone_target_path(),
-        pipeline_info.force_fetch_tags,
+        &args.remotes[0],
         &pipeline_info.git_username,
         &pipeline_info.git_token,
+        &template,
+        args.max_fetch_depth,
     )
-    .map_err(|e| {
-        Box::new(DefaultError {
-            message: "Failed to retrieve tags".to_string(),
-            source: Some(Box::new(e)),
-        })
-    })?;
+    .unwrap_or_else(|e| log::warn!("Failed to deepen shallow repository: {}", e));
 
     // Last official tag
-    let mut last_official_tag = git_service::last_tag_by_pattern(
-        &tag_names,
-        SEMANTIC_VERSION_TAG_OFFICIAL_PATTERN,
-        Some(SemanticVersion::default()),
-    )
-    .unwrap();
+    let (last_official_tag_name, last_official_tag) =
+        match git_service::tags_by_template(&tag_names, &template)
+            .into_iter()
+            .filter(|(_, version)| version.prerelease_stage.is_empty())
+            .last()
+        {
+            Some((tag_name, version)) => (Some(tag_name), version),
+            None => (None, SemanticVersion::default()),
+        };
+    explanation.add(
+        "last official",
+        match &last_official_tag_name {
+            Some(_) => template.render(&last_official_tag, scheme.as_ref()),
+            None => "none, starting from 0.0.0".to_string(),
+        },
+    );
 
     let upcoming_version;
     let last_version;
 
-    let prerelease_stage = prerelease_stage(&pipeline_info.branch_name);
-    // For release (main, master)
-    if args.scope == "release" || prerelease_stage.is_empty() {
-        upcoming_version = upcoming_official_version(&tag_names, &last_official_tag);
-        last_version = last_official_tag.to_string(true);
-    // For pre-release (develop, feature/*, release/*, hotfix/*)
-    } else {
-        let upcoming_official_version = last_official_tag.increase_by_scope(args.scope);
+    let pull_request = pipeline_info
+        .pull_request
+        .as_ref()
+        .filter(|_| args.pull_request_versions);
+    // Pull/merge requests are versioned against their target branch
+    let versioned_branch = match &pull_request {
+        Some(pull_request) => {
+            explanation.add(
+                "pull request",
+                format!(
+                    "#{} is versioned against its target branch {}",
+                    pull_request.number, pull_request.target_branch
+                ),
+            );
+            pull_request.target_branch.clone()
+        }
+        None => pipeline_info.branch_name.clone(),
+    };
+    let prerelease_stage = prerelease_stage(&versioned_branch);
+    explanation.add(
+        "stage",
+        match prerelease_stage.as_str() {
+            "" => format!(
+                "none, {} matches neither {} nor {}",
+                versioned_branch, DEV_PATTERN, RELEASE_CANDIDATE_PATTERN
+            ),
+            DEV_STAGE => format!("dev, {} matches {}", versioned_branch, DEV_PATTERN),
+            stage => format!(
+                "{}, {} matches {}",
+                stage, versioned_branch, RELEASE_CANDIDATE_PATTERN
+            ),
+        },
+    );
+    let is_release = args.scope == "release" || prerelease_stage.is_empty();
+    explanation.add(
+        "scope",
+        match (args.scope.as_str(), is_release) {
+            ("release", _) => "release, the next official version".to_string(),
+            (scope, true) => format!(
+                "{} ignored, a branch without a stage releases the next official version",
+                scope
+            ),
+            (scope, false) => format!("{}, a pre-release of the next {} version", scope, scope),
+        },
+    );
+    let scope = bump_scope(
+        args,
+        is_release,
+        last_official_tag_name.as_deref(),
+        &last_official_tag,
+        explanation,
+    )?;
+    let initial_version = initial_version(args, last_official_tag_name.as_deref())?;
+    // For pull/merge requests
+    if let Some(pull_request) = pull_request {
+        let upcoming_official_version = if is_release && !args.graduate {
+            upcoming_official_version(
+                args,
+                &tag_names,
+                last_official_tag_name.as_deref(),
+                &last_official_tag,
+                &template,
+                scheme.as_ref(),
+                explanation,
+            )?
+        } else {
+            bump(
+                &last_official_tag,
+                &scope,
+                initial_version,
+                scheme.as_ref(),
+                explanation,
+            )
+        };
+        let pull_request_stage = format!("{}.{}", PULL_REQUEST_STAGE, pull_request.number);
 
         upcoming_version = upcoming_prerelease_version(
             &tag_names,
-            prerelease_stage.clone(),
+            pull_request_stage.clone(),
             upcoming_official_version.clone(),
-            pipeline_info.short_commit_sha,
+            pipeline_info.short_commit_sha.clone(),
+            &template,
+            scheme.as_ref(),
+            explanation,
         );
 
+        last_version = last_prerelease_version(
+            &tag_names,
+            pull_request_stage,
+            last_official_tag,
+            &upcoming_official_version,
+            &template,
+            scheme.as_ref(),
+            explanation,
+        );
+    // For release (main, master)
+    } else if is_release {
+        let upcoming_official_version = match args.graduate {
+            true => increase(&last_official_tag, &scope, scheme.as_ref(), explanation),
+            false => upcoming_official_version(
+                args,
+                &tag_names,
+                last_official_tag_name.as_deref(),
+                &last_official_tag,
+                &template,
+                scheme.as_ref(),
+                explanation,
+            )?,
+        };
+        upcoming_version = template.render(&upcoming_official_version, scheme.as_ref());
+        last_version = template.render(&last_official_tag, scheme.as_ref());
+    // For pre-release (develop, feature/*, release/*, hotfix/*)
+    } else {
+        let upcoming_official_version = bump(
+            &last_official_tag,
+            &scope,
+            initial_version,
+            scheme.as_ref(),
+            explanation,
+        );
+
+        // Commits can only be counted since a tag of the repository, not e.g. a forge release
+        let mut number_by_commits = prerelease_stage == DEV_STAGE && args.dev_numbering == COMMITS;
+        if let Some(tag_name) = last_official_tag_name.as_deref().filter(|tag_name| {
+            number_by_commits && !git_service::has_tag(&config::clone_target_path(), tag_name)
+        }) {
+            let skipped = format!(
+                "{} is not a git tag, numbering {} after the tags instead of by the commits",
+                tag_name, DEV_STAGE
+            );
+            log::warn!("{}", skipped);
+            explanation.add("dev numbering", skipped);
+            number_by_commits = false;
+        }
+
+        upcoming_version = if number_by_commits {
+            let commits = git_service::commits_since(
+                &config::clone_target_path(),
+                last_official_tag_name.as_deref(),
+            )?;
+            // Never below an existing dev tag, e.g. after switching from tag numbering
+            let next_dev_number = git_service::last_tag_by_template(
+                &tag_names,
+                &template,
+                |version| is_prerelease_of(version, &upcoming_official_version, DEV_STAGE),
+                None,
+            )
+            .map_or(0, |version| version.prerelease_number + 1);
+            let dev_number = commits.max(next_dev_number);
+            explanation.add(
+                "bump",
+                format!(
+                    "prerelease {}.{}, the commits since {}, or after the last {} tag",
+                    DEV_STAGE,
+                    dev_number,
+                    last_official_tag_name
+                        .as_deref()
+                        .unwrap_or("the first commit"),
+                    DEV_STAGE
+                ),
+            );
+
+            let mut upcoming_dev_version = upcoming_official_version.clone();
+            upcoming_dev_version.prerelease_stage = DEV_STAGE.to_string();
+            upcoming_dev_version.prerelease_number = dev_number;
+            upcoming_dev_version
+                .commit_short_sha
+                .clone_from(&pipeline_info.short_commit_sha);
+            template.render(&upcoming_dev_version, scheme.as_ref())
+        } else {
+            upcoming_prerelease_version(
+                &tag_names,
+                prerelease_stage.clone(),
+                upcoming_official_version.clone(),
+                pipeline_info.short_commit_sha.clone(),
+                &template,
+                scheme.as_ref(),
+                explanation,
+            )
+        };
+
         last_version = last_prerelease_version(
             &tag_names,
             prerelease_stage,
             last_official_tag,
-            upcoming_official_version.to_string(false),
+            &upcoming_official_version,
+            &template,
+            scheme.as_ref(),
+            explanation,
         );
     }
 
-    println!("UPCOMING_VERSION={}", upcoming_version);
-    println!("LAST_VERSION={}", last_version);
+    commands::check_release_guards(&versioned_branch, &upcoming_version, &template)?;
 
-    Ok(())
+    Ok((upcoming_version, last_version))
+}
+
+/// Adds each tag, and whether it is a version tag of `template`, to `explanation`
+fn explain_tags(tag_names: &[String], template: &TagTemplate, explanation: &mut Explanation) {
+    for tag_name in tag_names {
+        let detail = match template.parse(tag_name) {
+            Some(version) if version.prerelease_stage.is_empty() => "official version".to_string(),
+            Some(version) => format!("{} pre-release", version.prerelease_stage),
+            None => format!(
+                "rejected, does not match the tag template {}",
+                template.as_str()
+            ),
+        };
+        explanation.add("tag", format!("{}: {}", tag_name, detail));
+    }
+}
+
+/// Scope after --graduate, --pre-1-0 and the breaking change guard. Releases keep their scope,
+/// their bump is checked by `upcoming_official_version`
+fn bump_scope(
+    args: &VersionArgs,
+    is_release: bool,
+    last_official_tag_name: Option<&str>,
+    last_official_version: &SemanticVersion,
+    explanation: &mut Explanation,
+) -> Result<String, Box<dyn Error>> {
+    if args.graduate {
+        if last_official_version.major > 0 {
+            return Err(Box::new(DefaultError {
+                message: format!(
+                    "Cannot graduate: the last official version {} is 1.0.0 or later",
+                    last_official_version.to_string(false)
+                ),
+                source: None,
+            }));
+        }
+        explanation.add("graduate", "major, 1.0.0 is next");
+        return Ok("major".to_string());
+    }
+    if is_release {
+        return Ok(args.scope.clone());
+    }
+
+    let scope = pre_1_0_scope(args, &args.scope, last_official_version, explanation);
+    breaking_changes::checked_scope(
+        &args.breaking_changes,
+        scope,
+        last_official_tag_name,
+        last_official_version,
+        explanation,
+    )
+}
+
+/// `scope` after --pre-1-0: minor for major and patch for minor while the major version is 0
+fn pre_1_0_scope<'a>(
+    args: &VersionArgs,
+    scope: &'a str,
+    last_official_version: &SemanticVersion,
+    explanation: &mut Explanation,
+) -> &'a str {
+    let mapped = match (args.pre_1_0 && last_official_version.major == 0, scope) {
+        (true, "major") => "minor",
+        (true, "minor") => "patch",
+        (_, scope) => scope,
+    };
+    if mapped != scope {
+        explanation.add(
+            "pre-1.0",
+            format!(
+                "{} instead of {} while the major version is 0",
+                mapped, scope
+            ),
+        );
+    }
+
+    mapped
+}
+
+/// --initial-version while there is no official tag
+fn initial_version(
+    args: &VersionArgs,
+    last_official_tag_name: Option<&str>,
+) -> Result<Option<SemanticVersion>, Box<dyn Error>> {
+    match (last_official_tag_name, &args.initial_version) {
+        (None, Some(initial_version)) if !args.graduate => {
+            Ok(Some(parse_initial_version(initial_version)?))
+        }
+        _ => Ok(None),
+    }
+}
+
+fn parse_initial_version(initial_version: &str) -> Result<SemanticVersion, Box<dyn Error>> {
+    match SemanticVersion::from_string(initial_version.to_string()) {
+        Ok(version) if version.prerelease_stage.is_empty() => Ok(version),
+        Ok(_) => Err(format!("{} is a pre-release", initial_version)),
+        Err(e) => Err(e),
+    }
+    .map_err(|e| {
+        Box::new(DefaultError {
+            message: format!("Invalid initial version: {}", e),
+            source: None,
+        }) as Box<dyn Error>
+    })
+}
+
+/// `initial_version` if there is one, else `version` increased by `scope`
+fn bump(
+    version: &SemanticVersion,
+    scope: &str,
+    initial_version: Option<SemanticVersion>,
+    scheme: &dyn VersionScheme,
+    explanation: &mut Explanation,
+) -> SemanticVersion {
+    match initial_version {
+        Some(initial_version) => {
+            explanation.add(
+                "initial",
+                format!(
+                    "{}, there is no official tag yet",
+                    scheme.core(&initial_version)
+                ),
+            );
+            initial_version
+        }
+        None => increase(version, scope, scheme, explanation),
+    }
 }
 
-fn prerelease_stage(branch_name: &str) -> String {
+fn increase(
+    version: &SemanticVersion,
+    scope: &str,
+    scheme: &dyn VersionScheme,
+    explanation: &mut Explanation,
+) -> SemanticVersion {
+    let increased = scheme.increase(version, scope);
+    explanation.add(
+        "bump",
+        format!(
+            "{} {} -> {}",
+            scope,
+            scheme.core(version),
+            scheme.core(&increased)
+        ),
+    );
+    increased
+}
+
+fn version_sources(
+    args: &VersionArgs,
+    pipeline: &dyn Pipeline,
+    pipeline_info: &PipelineInfo,
+) -> Result<Vec<Box<dyn VersionSource>>, Box<dyn Error>> {
+    let mut sources: Vec<Box<dyn VersionSource>> = vec![];
+    for source in &args.version_sources {
+        match source.as_str() {
+            GIT => sources.push(Box::new(GitTags {
+                repo_path: config::clone_target_path(),
+                force_fetch_tags: pipeline_info.force_fetch_tags,
+                remotes: args.remotes.clone(),
+                git_username: pipeline_info.git_username.clone(),
+                git_token: pipeline_info.git_token.clone(),
+            })),
+            FORGE => sources.push(Box::new(ForgeReleases {
+                forge: pipeline.forge()?,
+            })),
+            _ => {
+                let repository = args.oci_repository.as_ref().ok_or_else(|| DefaultError {
+                    message: "The oci version source needs --oci-repository".to_string(),
+                    source: None,
+                })?;
+                let token = config::first_env_var(&["OCI_TOKEN"]);
+                sources.push(Box::new(OciRegistry::new(repository, token)?));
+            }
+        }
+    }
+
+    Ok(sources)
+}
+
+/// Version of the tag the pipeline runs for, and the version before it: the previous official
+/// version, or for a pre-release also a previous pre-release of the same stage
+fn tag_versions(
+    tag_names: &[String],
+    tag_name: &str,
+    tag_prefix: &str,
+    template: &TagTemplate,
+    scheme: &dyn VersionScheme,
+    explanation: &mut Explanation,
+) -> Result<(String, String), Box<dyn Error>> {
+    if template.uses_prefix() && !tag_name.starts_with(tag_prefix) {
+        return Err(Box::new(DefaultError {
+            message: format!(
+                "Tag {} does not match the tag prefix \"{}\"",
+                tag_name, tag_prefix
+            ),
+            source: None,
+        }));
+    }
+    let version = template.parse(tag_name).ok_or_else(|| {
+        Box::new(DefaultError {
+            message: format!(
+                "Tag {} is not a semantic version: it does not match the tag template {}",
+                tag_name,
+                template.as_str()
+            ),
+            source: None,
+        })
+    })?;
+
+    let last_version = git_service::tags_by_template(tag_names, template)
+        .into_iter()
+        .map(|(_, previous)| previous)
+        .filter(|previous| {
+            previous.prerelease_stage.is_empty()
+                || previous.prerelease_stage == version.prerelease_stage
+        })
+        .filter(|previous| previous < &version)
+        .max();
+    explanation.add(
+        "last version",
+        match &last_version {
+            Some(last_version) => format!(
+                "{}, the previous official version or {} pre-release",
+                template.render(last_version, scheme),
+                version.prerelease_stage
+            ),
+            None => "none, starting from 0.0.0".to_string(),
+        },
+    );
+    let last_version = last_version.unwrap_or_else(SemanticVersion::default);
+
+    Ok((
+        template.render(&version, scheme),
+        template.render(&last_version, scheme),
+    ))
+}
+
+pub(crate) fn prerelease_stage(branch_name: &str) -> String {
     let dev_regex = Regex::new(DEV_PATTERN).unwrap_or_else(|e| panic!("{}", e));
     let release_candidate_regex =
         Regex::new(RELEASE_CANDIDATE_PATTERN).unwrap_or_else(|e| panic!("{}", e));
 
     let stage = if dev_regex.is_match(branch_name) {
-        "dev"
+        DEV_STAGE
     } else if release_candidate_regex.is_match(branch_name) {
         "rc"
     } else {
@@ -97,77 +844,159 @@ fn prerelease_stage(branch_name: &str) -> String {
 }
 
 fn upcoming_official_version(
-    tag_names: &StringArray,
+    args: &VersionArgs,
+    tag_names: &[String],
+    last_official_tag_name: Option<&str>,
     last_official_version: &SemanticVersion,
-) -> String {
-    match git_service::last_tag_by_pattern(tag_names, SEMANTIC_VERSION_TAG_PRERELEASE_PATTERN, None)
+    template: &TagTemplate,
+    scheme: &dyn VersionScheme,
+    explanation: &mut Explanation,
+) -> Result<SemanticVersion, Box<dyn Error>> {
+    let is_prerelease = |version: &SemanticVersion| !version.prerelease_stage.is_empty();
+    let fallback_scope = pre_1_0_scope(args, "minor", last_official_version, explanation);
+    let fallback = match git_service::last_tag_by_template(tag_names, template, is_prerelease, None)
     {
         Some(mut last_prerelease_tag) => match last_prerelease_tag.cmp(last_official_version) {
-            Ordering::Greater => last_prerelease_tag.release().to_string(true),
-            _ => {
-                log::warn!(
-                    "No newer pre-release after last official tag ({}). Fallback to minor bump.",
-                    last_official_version.to_string(true)
+            Ordering::Greater => {
+                let release = last_prerelease_tag.release();
+                explanation.add(
+                    "release",
+                    format!(
+                        "{} of the last pre-release {}",
+                        scheme.core(&release),
+                        template.render(&last_prerelease_tag, scheme)
+                    ),
                 );
-                last_official_version
-                    .clone()
-                    .increase_by_scope("minor".to_string())
-                    .to_string(true)
+                // Breaking changes are checked against the bump the release amounts to
+                let released_scope = released_scope(last_official_version, &release);
+                let scope = breaking_changes::checked_scope(
+                    &args.breaking_changes,
+                    released_scope,
+                    last_official_tag_name,
+                    last_official_version,
+                    explanation,
+                )?;
+                return Ok(match scope == released_scope {
+                    true => release,
+                    false => increase(last_official_version, &scope, scheme, explanation),
+                });
             }
+            _ => format!(
+                "No newer pre-release after last official tag ({}). Fallback to {} bump.",
+                last_official_version.to_string(true),
+                fallback_scope
+            ),
         },
-        None => {
-            log::warn!(
-                "No pre-release tags found. Fallback to minor bump from last official ({}).",
-                last_official_version.to_string(true)
-            );
-            last_official_version
-                .clone()
-                .increase_by_scope("minor".to_string())
-                .to_string(true)
-        }
+        None => format!(
+            "No pre-release tags found. Fallback to {} bump from last official ({}).",
+            fallback_scope,
+            last_official_version.to_string(true)
+        ),
+    };
+    let scope = breaking_changes::checked_scope(
+        &args.breaking_changes,
+        fallback_scope,
+        last_official_tag_name,
+        last_official_version,
+        explanation,
+    )?;
+
+    let initial_version = initial_version(args, last_official_tag_name)?;
+    if initial_version.is_some() {
+        return Ok(bump(
+            last_official_version,
+            &scope,
+            initial_version,
+            scheme,
+            explanation,
+        ));
+    }
+    log::warn!("{}", fallback);
+    explanation.add("fallback", fallback);
+    Ok(increase(last_official_version, &scope, scheme, explanation))
+}
+
+/// Scope of the bump from the official version `last` to `release`
+fn released_scope(last: &SemanticVersion, release: &SemanticVersion) -> &'static str {
+    if release.major > last.major {
+        "major"
+    } else if release.minor > last.minor {
+        "minor"
+    } else {
+        "patch"
     }
 }
 
 fn upcoming_prerelease_version(
-    tag_names: &StringArray,
+    tag_names: &[String],
     prerelease_stage: String,
     mut upcoming_official_version: SemanticVersion,
     commit_short_sha: String,
+    template: &TagTemplate,
+    scheme: &dyn VersionScheme,
+    explanation: &mut Explanation,
 ) -> String {
-    let upcoming_official_version_string = upcoming_official_version.to_string(false);
     upcoming_official_version
         .prerelease_stage
         .clone_from(&prerelease_stage);
 
-    let mut upcoming_prerelease_version = git_service::last_tag_by_pattern(
+    let last_prerelease_version = git_service::last_tag_by_template(
         tag_names,
-        &format!(
-            r"^v?{}-{}\.[0-9]+.*$",
-            upcoming_official_version_string, prerelease_stage
-        ),
-        Some(upcoming_official_version),
-    )
-    .unwrap()
-    .increase_by_scope("prerelease".to_string());
+        template,
+        |version| is_prerelease_of(version, &upcoming_official_version, &prerelease_stage),
+        None,
+    );
+    let mut upcoming_prerelease_version = last_prerelease_version
+        .unwrap_or(upcoming_official_version)
+        .increase_by_scope("prerelease".to_string());
     upcoming_prerelease_version.commit_short_sha = commit_short_sha;
+    explanation.add(
+        "bump",
+        format!(
+            "prerelease {}.{}",
+            prerelease_stage, upcoming_prerelease_version.prerelease_number
+        ),
+    );
 
-    upcoming_prerelease_version.to_string(true)
+    template.render(&upcoming_prerelease_version, scheme)
 }
 
 fn last_prerelease_version(
-    tag_names: &StringArray,
+    tag_names: &[String],
     prerelease_stage: String,
     last_official_version: SemanticVersion,
-    upcoming_official_version: String,
+    upcoming_official_version: &SemanticVersion,
+    template: &TagTemplate,
+    scheme: &dyn VersionScheme,
+    explanation: &mut Explanation,
 ) -> String {
-    git_service::last_tag_by_pattern(
+    let last_prerelease_version = git_service::last_tag_by_template(
         tag_names,
-        &format!(
-            r"^v?{}-{}\.[0-9]+.*$",
-            upcoming_official_version, prerelease_stage
-        ),
-        Some(last_official_version),
+        template,
+        |version| is_prerelease_of(version, upcoming_official_version, &prerelease_stage),
+        None,
+    );
+    explanation.add(
+        "last pre-release",
+        match &last_prerelease_version {
+            Some(version) => template.render(version, scheme),
+            None => format!(
+                "none of {}-{}, the last version is the last official",
+                scheme.core(upcoming_official_version),
+                prerelease_stage
+            ),
+        },
+    );
+
+    template.render(
+        &last_prerelease_version.unwrap_or(last_official_version),
+        scheme,
     )
-    .unwrap()
-    .to_string(true)
+}
+
+/// Whether `version` is a `stage` pre-release of the official version `official`
+fn is_prerelease_of(version: &SemanticVersion, official: &SemanticVersion, stage: &str) -> bool {
+    (version.major, version.minor, version.patch)
+        == (official.major, official.minor, official.patch)
+        && version.prerelease_stage == stage
 }
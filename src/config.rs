@@ -1,3 +1,7 @@
+use crate::default_error::DefaultError;
+use crate::semantic_version::VersionReq;
+use crate::tag_template::{TagTemplate, DEFAULT_TAG_TEMPLATE};
+use regex::Regex;
 use std::env;
 
 pub(crate) fn env_var(name: &str) -> String {
@@ -8,6 +12,13 @@ pub(crate) fn env_var_or(name: &str, default: &str) -> String {
     env::var(name).unwrap_or(default.to_string())
 }
 
+pub(crate) fn first_env_var(names: &[&str]) -> Option<String> {
+    names
+        .iter()
+        .filter_map(|name| env::var(name).ok())
+        .find(|value| !value.is_empty())
+}
+
 pub(crate) fn clone_target_path() -> String {
     env::var("CLONE_TARGET_PATH").unwrap_or(".".to_string())
 }
@@ -19,3 +30,53 @@ fn environment() -> String {
 pub(crate) fn is_test() -> bool {
     environment() == "test"
 }
+
+pub(crate) fn ca_bundle() -> Option<String> {
+    first_env_var(&["FORGE_CA_BUNDLE"])
+}
+
+/// Makes git (vendored OpenSSL) trust the same CA bundle as the forge API client.
+pub(crate) fn apply_ca_bundle() {
+    if let Some(path) = ca_bundle() {
+        env::set_var("SSL_CERT_FILE", path);
+    }
+}
+
+/// Release guards from RELEASE_GUARDS: `<branch pattern>:<version requirement>` entries
+/// separated by `;`, e.g. `^release/1\..*$:^1;^hotfix/.*$:~1.4`
+pub(crate) fn release_guards() -> Result<Vec<(Regex, VersionReq)>, DefaultError> {
+    let invalid = |guard: &str, reason: String| DefaultError {
+        message: format!("Invalid RELEASE_GUARDS entry \"{}\": {}", guard, reason),
+        source: None,
+    };
+    env_var_or("RELEASE_GUARDS", "")
+        .split(';')
+        .map(str::trim)
+        .filter(|guard| !guard.is_empty())
+        .map(|guard| {
+            let (pattern, req) = guard.rsplit_once(':').ok_or_else(|| {
+                invalid(
+                    guard,
+                    "expected <branch pattern>:<version requirement>".to_string(),
+                )
+            })?;
+            let regex = Regex::new(pattern).map_err(|e| invalid(guard, e.to_string()))?;
+            let req = VersionReq::parse(req).map_err(|e| invalid(guard, e))?;
+            Ok((regex, req))
+        })
+        .collect()
+}
+
+/// Tag template from TAG_TEMPLATE with `prefix` as `{prefix}`
+pub(crate) fn tag_template(prefix: &str) -> Result<TagTemplate, DefaultError> {
+    let template = env_var_or("TAG_TEMPLATE", DEFAULT_TAG_TEMPLATE);
+    TagTemplate::new(&template, prefix).map_err(|e| DefaultError {
+        message: format!("TAG_TEMPLATE: {}", e),
+        source: None,
+    })
+}
+
+/// Tag template with the tag prefix from TAG_PREFIX
+pub(crate) fn default_tag_template() -> Result<TagTemplate, DefaultError> {
+    tag_template(&env_var_or("TAG_PREFIX", "v"))
+}
@@ -0,0 +1,123 @@
+use regex::Regex;
+
+const BREAKING_CHANGE_TOKENS: [&str; 2] = ["BREAKING CHANGE", "BREAKING-CHANGE"];
+
+const HEADER_PATTERN: &str =
+    r"^(?P<type>[A-Za-z]+)(?:\((?P<scope>[^()\s]+)\))?(?P<breaking>!)?: (?P<description>\S.*)$";
+const FOOTER_PATTERN: &str =
+    r"^(?P<token>BREAKING CHANGE|[A-Za-z][A-Za-z-]*)(?:: | #)(?P<value>.*)$";
+
+/// Commit message of the Conventional Commits specification,
+/// `<type>[(<scope>)][!]: <description>`, an optional body and footers
+#[derive(Debug)]
+pub(crate) struct ConventionalCommit {
+    pub(crate) header: String,
+    pub(crate) commit_type: String,
+    pub(crate) scope: Option<String>,
+    /// `!` after the type or scope, or a BREAKING CHANGE footer
+    pub(crate) breaking: bool,
+    pub(crate) footers: Vec<(String, String)>,
+}
+
+impl ConventionalCommit {
+    /// Parses a message, ignoring `#` comment lines as git does
+    pub(crate) fn parse(message: &str) -> Result<Self, String> {
+        let lines: Vec<&str> = message
+            .lines()
+            .filter(|line| !line.starts_with('#'))
+            .map(str::trim_end)
+            .collect();
+        let header = lines.first().copied().unwrap_or_default().to_string();
+        let captures = Regex::new(HEADER_PATTERN)
+            .unwrap()
+            .captures(&header)
+            .ok_or_else(|| {
+                format!(
+                    "header \"{}\" is not <type>[(<scope>)][!]: <description>",
+                    header
+                )
+            })?;
+        if lines.get(1).is_some_and(|line| !line.is_empty()) {
+            return Err("a blank line must follow the header".to_string());
+        }
+
+        let paragraphs: Vec<&[&str]> = lines
+            .get(2..)
+            .unwrap_or_default()
+            .split(|line| line.is_empty())
+            .filter(|paragraph| !paragraph.is_empty())
+            .collect();
+        let footer_regex = Regex::new(FOOTER_PATTERN).unwrap();
+        let footers = match paragraphs.last() {
+            Some(last) if footer_regex.is_match(last[0]) => footers(&footer_regex, last),
+            _ => vec![],
+        };
+        let breaking = captures.name("breaking").is_some()
+            || footers
+                .iter()
+                .any(|(token, _)| BREAKING_CHANGE_TOKENS.contains(&token.as_str()));
+
+        Ok(ConventionalCommit {
+            header: header.clone(),
+            commit_type: captures["type"].to_string(),
+            scope: captures.name("scope").map(|m| m.as_str().to_string()),
+            breaking,
+            footers,
+        })
+    }
+}
+
+/// Footers of the last paragraph. Lines not starting a footer continue the previous one
+fn footers(footer_regex: &Regex, lines: &[&str]) -> Vec<(String, String)> {
+    let mut footers: Vec<(String, String)> = vec![];
+    for line in lines {
+        match (footer_regex.captures(line), footers.last_mut()) {
+            (Some(captures), _) => {
+                footers.push((captures["token"].to_string(), captures["value"].to_string()))
+            }
+            (None, Some((_, value))) => {
+                value.push('\n');
+                value.push_str(line);
+            }
+            (None, None) => {}
+        }
+    }
+
+    footers
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_messages() {
+        let commit = ConventionalCommit::parse(
+            "feat(api)!: drop v1\n\nThe v1 endpoints are gone.\n\nRefs #42\nBREAKING CHANGE: v1 clients\n  must migrate\n",
+        )
+        .unwrap();
+        assert_eq!(commit.commit_type, "feat");
+        assert_eq!(commit.scope.as_deref(), Some("api"));
+        assert_eq!(
+            commit.footers,
+            vec![
+                ("Refs".to_string(), "42".to_string()),
+                (
+                    "BREAKING CHANGE".to_string(),
+                    "v1 clients\n  must migrate".to_string()
+                ),
+            ]
+        );
+        assert!(commit.breaking);
+        let commit =
+            ConventionalCommit::parse("docs: readme\n# comment\n\nSee the README").unwrap();
+        assert!(commit.footers.is_empty());
+        assert!(!commit.breaking);
+        let commit = ConventionalCommit::parse("fix: rename\n\nBREAKING-CHANGE: renamed").unwrap();
+        assert!(commit.breaking);
+
+        assert!(ConventionalCommit::parse("update readme").is_err());
+        assert!(ConventionalCommit::parse("feat: x\nno blank line").is_err());
+        assert!(ConventionalCommit::parse("feat:missing space").is_err());
+    }
+}
@@ -0,0 +1,30 @@
+use serde_json::{json, Value};
+use std::fmt::Write;
+
+/// Steps of how a version was derived, printed by `svci explain`
+#[derive(Default)]
+pub(crate) struct Explanation {
+    steps: Vec<(String, String)>,
+}
+
+impl Explanation {
+    pub(crate) fn add(&mut self, step: &str, detail: impl Into<String>) {
+        self.steps.push((step.to_string(), detail.into()));
+    }
+
+    pub(crate) fn to_text(&self) -> String {
+        self.steps
+            .iter()
+            .fold(String::new(), |mut text, (step, detail)| {
+                let _ = writeln!(text, "{}: {}", step, detail);
+                text
+            })
+    }
+
+    pub(crate) fn to_json(&self) -> Value {
+        self.steps
+            .iter()
+            .map(|(step, detail)| json!({ "step": step, "detail": detail }))
+            .collect()
+    }
+}
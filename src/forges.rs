@@ -0,0 +1,356 @@
+mod gitea;
+mod github;
+mod gitlab;
+
+use crate::default_error::DefaultError;
+use crate::forges::gitea::Gitea;
+use crate::forges::github::Github;
+use crate::forges::gitlab::Gitlab;
+use crate::release::Release;
+use crate::{config, git_service};
+use reqwest::Url;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::error::Error;
+
+pub(crate) const GITHUB: &str = "github";
+pub(crate) const GITLAB: &str = "gitlab";
+pub(crate) const GITEA: &str = "gitea";
+
+const GITHUB_SERVER_URL: &str = "https://github.com";
+const GITHUB_API_URL: &str = "https://api.github.com";
+const GITLAB_SERVER_URL: &str = "https://gitlab.com";
+
+pub(crate) trait Forge {
+    fn name(&self) -> String;
+    fn create_release(
+        &self,
+        release: &Release,
+        target_commit: &str,
+    ) -> Result<HashMap<String, Value>, Box<dyn Error>>;
+    /// Description of the release of `tag_name`
+    fn release_description(&self, tag_name: &str) -> Result<String, Box<dyn Error>>;
+    /// Tag names of all releases, from every page of the releases API
+    fn release_tag_names(&self) -> Result<Vec<String>, Box<dyn Error>>;
+    /// Names of the checks of `commit_sha` that failed. Pending checks don't count
+    fn failed_checks(&self, commit_sha: &str) -> Result<Vec<String>, Box<dyn Error>>;
+    /// Deletes the release of `tag_name`, keeping the tag. False if there is no release
+    fn delete_release(&self, tag_name: &str) -> Result<bool, Box<dyn Error>>;
+}
+
+/// Values of `name_key` of the `items` whose `state_key` is one of `failed_states`
+fn failed_names(
+    items: &[Value],
+    name_key: &str,
+    state_key: &str,
+    failed_states: &[&str],
+) -> Vec<String> {
+    items
+        .iter()
+        .filter(|item| {
+            item.get(state_key)
+                .and_then(Value::as_str)
+                .is_some_and(|state| failed_states.contains(&state))
+        })
+        .filter_map(|item| item.get(name_key).and_then(Value::as_str))
+        .map(str::to_string)
+        .collect()
+}
+
+/// Tag names of the releases on `pages` of release lists
+fn tag_names_of(pages: Vec<Value>) -> Vec<String> {
+    pages
+        .iter()
+        .filter_map(Value::as_array)
+        .flatten()
+        .filter_map(|release| release.get("tag_name").and_then(Value::as_str))
+        .map(str::to_string)
+        .collect()
+}
+
+/// Connection settings of a forge. Each field is taken from its FORGE_* variable first,
+/// then from the variables of the running pipeline, then from the given defaults.
+pub(crate) struct ForgeConfig {
+    pub(crate) forge_type: String,
+    pub(crate) server_url: String,
+    pub(crate) api_url: String,
+    pub(crate) token_env: String,
+    pub(crate) repository: String,
+    pub(crate) project_url: String,
+}
+
+impl ForgeConfig {
+    pub(crate) fn github() -> Self {
+        Self::github_with(GITHUB_SERVER_URL, "")
+    }
+
+    pub(crate) fn gitlab() -> Self {
+        Self::gitlab_with(GITLAB_SERVER_URL, "")
+    }
+
+    pub(crate) fn gitea() -> Self {
+        Self::gitea_with("", "")
+    }
+
+    fn github_with(default_server_url: &str, default_repository: &str) -> Self {
+        let server_url = server_url(&["GITHUB_SERVER_URL"], default_server_url);
+        let api_url = api_url(&["GITHUB_API_URL"]).unwrap_or_else(|| {
+            if server_url == GITHUB_SERVER_URL {
+                GITHUB_API_URL.to_string()
+            } else {
+                format!("{}/api/v3", server_url)
+            }
+        });
+        let repository = repository(&["GITHUB_REPOSITORY"], default_repository);
+
+        ForgeConfig {
+            forge_type: GITHUB.to_string(),
+            project_url: format!("{}/{}", server_url, repository),
+            server_url,
+            api_url,
+            token_env: token_env(&["GITHUB_TOKEN", "GH_TOKEN", "GIT_TOKEN"]),
+            repository,
+        }
+    }
+
+    fn gitlab_with(default_server_url: &str, default_repository: &str) -> Self {
+        let server_url = server_url(&["CI_SERVER_URL"], default_server_url);
+        let api_url = api_url(&["CI_API_V4_URL"]).unwrap_or(format!("{}/api/v4", server_url));
+        let repository = repository(&["CI_PROJECT_ID"], default_repository);
+        let project_url = match config::first_env_var(&["FORGE_URL"]) {
+            Some(_) => format!("{}/{}", server_url, repository),
+            None => config::env_var_or("CI_PROJECT_URL", &format!("{}/{}", server_url, repository)),
+        };
+
+        ForgeConfig {
+            forge_type: GITLAB.to_string(),
+            server_url,
+            api_url,
+            token_env: token_env(&["CI_JOB_TOKEN", "GITLAB_TOKEN", "GIT_TOKEN"]),
+            repository,
+            project_url,
+        }
+    }
+
+    fn gitea_with(default_server_url: &str, default_repository: &str) -> Self {
+        let server_url = server_url(
+            &["GITEA_SERVER_URL", "GITHUB_SERVER_URL"],
+            default_server_url,
+        );
+        let api_url = api_url(&[]).unwrap_or(format!("{}/api/v1", server_url));
+        let repository = repository(&["GITHUB_REPOSITORY"], default_repository);
+
+        ForgeConfig {
+            forge_type: GITEA.to_string(),
+            project_url: format!("{}/{}", server_url, repository),
+            server_url,
+            api_url,
+            token_env: token_env(&["GITEA_TOKEN", "GITHUB_TOKEN", "GIT_TOKEN"]),
+            repository,
+        }
+    }
+
+    /// Forge of a plain git repo. FORGE_TYPE wins, otherwise the forge is inferred from
+    /// the host of the remote URL, which also provides the server URL and repository.
+    pub(crate) fn detect(remote_url: Option<String>) -> Result<Option<Self>, Box<dyn Error>> {
+        let remote = remote_url.as_deref().and_then(parse_remote_url);
+        let forge_type = match config::first_env_var(&["FORGE_TYPE"]) {
+            Some(forge_type) => forge_type.to_lowercase(),
+            None => match remote
+                .as_ref()
+                .and_then(|remote| forge_type_by_host(&remote.host))
+            {
+                Some(forge_type) => forge_type,
+                None => return Ok(None),
+            },
+        };
+        let (server_url, repository) = match &remote {
+            Some(remote) => (Some(remote.server_url.as_str()), remote.path.as_str()),
+            None => (None, ""),
+        };
+
+        let forge_config = match forge_type.as_str() {
+            GITHUB => Self::github_with(server_url.unwrap_or(GITHUB_SERVER_URL), repository),
+            GITLAB => Self::gitlab_with(server_url.unwrap_or(GITLAB_SERVER_URL), repository),
+            GITEA => Self::gitea_with(server_url.unwrap_or(""), repository),
+            other => {
+                return Err(Box::new(DefaultError {
+                    message: format!(
+                        "Invalid FORGE_TYPE: {}. Expected {}, {} or {}",
+                        other, GITHUB, GITLAB, GITEA
+                    ),
+                    source: None,
+                }))
+            }
+        };
+        Ok(Some(forge_config))
+    }
+
+    /// Token from the configured variable, falling back to git's credential helper for the forge host
+    pub(crate) fn token(&self) -> String {
+        config::first_env_var(&[&self.token_env])
+            .or_else(|| {
+                git_service::credential_helper_password(
+                    &config::clone_target_path(),
+                    &self.server_url,
+                )
+            })
+            .unwrap_or_else(|| {
+                panic!(
+                    "environment variable not found: \"{}\" (no credential from git credential helper either)",
+                    self.token_env
+                )
+            })
+    }
+
+    pub(crate) fn is_token_from_env(&self) -> bool {
+        config::first_env_var(&[&self.token_env]).is_some()
+    }
+}
+
+struct RemoteUrl {
+    host: String,
+    server_url: String,
+    path: String,
+}
+
+/// Parses `https://[user@]host[:port]/path(.git)`, `ssh://[user@]host[:port]/path(.git)`
+/// and scp-like `[user@]host:path(.git)` remote URLs.
+fn parse_remote_url(url: &str) -> Option<RemoteUrl> {
+    let (scheme, host, port, path) = if url.contains("://") {
+        let parsed = Url::parse(url).ok()?;
+        (
+            parsed.scheme().to_string(),
+            parsed.host_str()?.to_string(),
+            parsed.port(),
+            parsed.path().to_string(),
+        )
+    } else {
+        let (authority, path) = url.split_once(':')?;
+        let host = authority.rsplit('@').next()?;
+        ("ssh".to_string(), host.to_string(), None, path.to_string())
+    };
+
+    let server_url = match (scheme.as_str(), port) {
+        ("http" | "https", Some(port)) => format!("{}://{}:{}", scheme, host, port),
+        ("http" | "https", None) => format!("{}://{}", scheme, host),
+        _ => format!("https://{}", host),
+    };
+    let path = path.trim_matches('/');
+    let path = path.strip_suffix(".git").unwrap_or(path).to_string();
+    if host.is_empty() || path.is_empty() {
+        return None;
+    }
+
+    Some(RemoteUrl {
+        host,
+        server_url,
+        path,
+    })
+}
+
+/// Well-known hosts plus FORGE_HOSTS, e.g. `ghe.example.com=github,git.example.com=gitlab`
+fn forge_type_by_host(host: &str) -> Option<String> {
+    let configured = config::first_env_var(&["FORGE_HOSTS"]).unwrap_or_default();
+    let configured_type = configured
+        .split(',')
+        .filter_map(|entry| entry.split_once('='))
+        .find(|(configured_host, _)| configured_host.trim() == host)
+        .map(|(_, forge_type)| forge_type.trim().to_lowercase());
+    if configured_type.is_some() {
+        return configured_type;
+    }
+
+    match host {
+        "github.com" => Some(GITHUB.to_string()),
+        "gitlab.com" => Some(GITLAB.to_string()),
+        "gitea.com" | "codeberg.org" => Some(GITEA.to_string()),
+        _ => None,
+    }
+}
+
+pub(crate) fn forge(config: ForgeConfig) -> Result<Box<dyn Forge>, Box<dyn Error>> {
+    if config.repository.is_empty() {
+        return Err(Box::new(DefaultError {
+            message: format!(
+                "Repository of {} is unknown. Set FORGE_REPOSITORY",
+                config.forge_type
+            ),
+            source: None,
+        }));
+    }
+
+    match config.forge_type.as_str() {
+        GITHUB => Ok(Box::new(Github { config })),
+        GITLAB => Ok(Box::new(Gitlab { config })),
+        GITEA => Ok(Box::new(Gitea { config })),
+        other => Err(Box::new(DefaultError {
+            message: format!("Not supported forge: {}", other),
+            source: None,
+        })),
+    }
+}
+
+fn server_url(env_names: &[&str], default: &str) -> String {
+    let names = [&["FORGE_URL"], env_names].concat();
+    config::first_env_var(&names)
+        .unwrap_or(default.to_string())
+        .trim_end_matches('/')
+        .to_string()
+}
+
+fn api_url(env_names: &[&str]) -> Option<String> {
+    let names = [&["FORGE_API_URL"], env_names].concat();
+    config::first_env_var(&names).map(|url| url.trim_end_matches('/').to_string())
+}
+
+fn token_env(env_names: &[&str]) -> String {
+    config::first_env_var(&["FORGE_TOKEN_ENV"]).unwrap_or_else(|| {
+        env_names
+            .iter()
+            .find(|name| config::first_env_var(&[name]).is_some())
+            .unwrap_or(&env_names[0])
+            .to_string()
+    })
+}
+
+fn repository(env_names: &[&str], default: &str) -> String {
+    let names = [&["FORGE_REPOSITORY"], env_names].concat();
+    config::first_env_var(&names).unwrap_or(default.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_https_and_ssh_remote_urls() {
+        let https = parse_remote_url("https://github.com/owner/repo.git").unwrap();
+        assert_eq!(https.host, "github.com");
+        assert_eq!(https.server_url, "https://github.com");
+        assert_eq!(https.path, "owner/repo");
+
+        let port = parse_remote_url("http://user@git.example.com:8080/group/sub/project").unwrap();
+        assert_eq!(port.server_url, "http://git.example.com:8080");
+        assert_eq!(port.path, "group/sub/project");
+
+        let scp = parse_remote_url("git@gitlab.com:group/project.git").unwrap();
+        assert_eq!(scp.host, "gitlab.com");
+        assert_eq!(scp.server_url, "https://gitlab.com");
+        assert_eq!(scp.path, "group/project");
+
+        let ssh = parse_remote_url("ssh://git@git.example.com:2222/owner/repo.git").unwrap();
+        assert_eq!(ssh.server_url, "https://git.example.com");
+        assert_eq!(ssh.path, "owner/repo");
+
+        assert!(parse_remote_url("/local/path/repo").is_none());
+    }
+
+    #[test]
+    fn forge_type_of_well_known_hosts() {
+        assert_eq!(forge_type_by_host("github.com").as_deref(), Some(GITHUB));
+        assert_eq!(forge_type_by_host("gitlab.com").as_deref(), Some(GITLAB));
+        assert_eq!(forge_type_by_host("codeberg.org").as_deref(), Some(GITEA));
+        assert_eq!(forge_type_by_host("git.example.com"), None);
+    }
+}
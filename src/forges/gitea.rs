@@ -0,0 +1,147 @@
+use crate::default_error::DefaultError;
+use crate::forges::{self, Forge, ForgeConfig};
+use crate::release::Release;
+use crate::{config, http_service};
+use log::warn;
+use reqwest::header::HeaderMap;
+use reqwest::Url;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::error::Error;
+use std::path::Path;
+
+pub(crate) struct Gitea {
+    pub(crate) config: ForgeConfig,
+}
+
+impl Forge for Gitea {
+    fn name(&self) -> String {
+        "Gitea".to_string()
+    }
+
+    fn create_release(
+        &self,
+        release: &Release,
+        target_commit: &str,
+    ) -> Result<HashMap<String, Value>, Box<dyn Error>> {
+        let url = format!(
+            "{}/repos/{}/releases",
+            self.config.api_url, self.config.repository
+        );
+
+        if release.generate_release_notes {
+            warn!("Gitea does not support generating release notes. Only the description is used.");
+        }
+
+        let mut body = HashMap::new();
+        body.insert("name", json!(release.name.clone()));
+        body.insert("body", json!(release.description.clone()));
+        body.insert("tag_name", json!(release.tag_name.clone()));
+        body.insert("target_commitish", json!(target_commit));
+        let version = config::default_tag_template()?.parse(&release.tag_name);
+        let prerelease = version.is_some_and(|version| !version.prerelease_stage.is_empty());
+        body.insert("prerelease", json!(prerelease));
+
+        let parsed = http_service::post(url, Some(self.headers()), Some(body))?;
+
+        for attachment in &release.attachments {
+            self.upload_attachment(&parsed, attachment)?;
+        }
+
+        Ok(parsed)
+    }
+
+    fn release_description(&self, tag_name: &str) -> Result<String, Box<dyn Error>> {
+        let url = format!(
+            "{}/repos/{}/releases/tags/{}",
+            self.config.api_url, self.config.repository, tag_name
+        );
+
+        let parsed = http_service::get(url, Some(self.headers()), None)?;
+        Ok(parsed
+            .get("body")
+            .and_then(Value::as_str)
+            .unwrap_or("")
+            .to_string())
+    }
+
+    fn release_tag_names(&self) -> Result<Vec<String>, Box<dyn Error>> {
+        let url = format!(
+            "{}/repos/{}/releases",
+            self.config.api_url, self.config.repository
+        );
+        let query = HashMap::from([("limit", "50")]);
+
+        let pages = http_service::get_pages(url, Some(self.headers()), Some(query))?;
+        Ok(forges::tag_names_of(pages))
+    }
+
+    fn failed_checks(&self, commit_sha: &str) -> Result<Vec<String>, Box<dyn Error>> {
+        let url = format!(
+            "{}/repos/{}/commits/{}/status",
+            self.config.api_url, self.config.repository, commit_sha
+        );
+
+        let parsed = http_service::get(url, Some(self.headers()), None)?;
+        let statuses = parsed.get("statuses").and_then(Value::as_array);
+        Ok(forges::failed_names(
+            statuses.map_or(&[], Vec::as_slice),
+            "context",
+            "status",
+            &["failure", "error"],
+        ))
+    }
+
+    fn delete_release(&self, tag_name: &str) -> Result<bool, Box<dyn Error>> {
+        let url = format!(
+            "{}/repos/{}/releases/tags/{}",
+            self.config.api_url, self.config.repository, tag_name
+        );
+
+        http_service::delete(url, Some(self.headers()))
+    }
+}
+
+impl Gitea {
+    fn headers(&self) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert("User-Agent", "Semver-CI".parse().unwrap());
+        headers.insert("Accept", "application/json".parse().unwrap());
+        headers.insert(
+            "Authorization",
+            format!("token {}", self.config.token()).parse().unwrap(),
+        );
+        headers
+    }
+
+    fn upload_attachment(
+        &self,
+        release: &HashMap<String, Value>,
+        file_path: &str,
+    ) -> Result<HashMap<String, Value>, Box<dyn Error>> {
+        let release_id = release
+            .get("id")
+            .and_then(Value::as_u64)
+            .ok_or_else(|| DefaultError {
+                message: "Release id is missing in the Gitea response".to_string(),
+                source: None,
+            })?;
+        let file_name = Path::new(file_path)
+            .file_name()
+            .and_then(|name| name.to_str())
+            .unwrap_or(file_path);
+
+        let mut url = Url::parse(&format!(
+            "{}/repos/{}/releases/{}/assets",
+            self.config.api_url, self.config.repository, release_id
+        ))?;
+        url.query_pairs_mut().append_pair("name", file_name);
+
+        http_service::upload(
+            url.to_string(),
+            Some(self.headers()),
+            "attachment",
+            file_path,
+        )
+    }
+}
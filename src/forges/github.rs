@@ -0,0 +1,137 @@
+use crate::default_error::DefaultError;
+use crate::forges::{self, Forge, ForgeConfig};
+use crate::http_service;
+use crate::release::Release;
+use reqwest::header::HeaderMap;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::error::Error;
+
+pub(crate) struct Github {
+    pub(crate) config: ForgeConfig,
+}
+
+impl Forge for Github {
+    fn name(&self) -> String {
+        "GitHub".to_string()
+    }
+
+    fn create_release(
+        &self,
+        release: &Release,
+        target_commit: &str,
+    ) -> Result<HashMap<String, Value>, Box<dyn Error>> {
+        let url = format!(
+            "{}/repos/{}/releases",
+            self.config.api_url, self.config.repository
+        );
+
+        let mut body = HashMap::new();
+        body.insert("name", json!(release.name.clone()));
+        body.insert("body", json!(release.description.clone()));
+        body.insert("tag_name", json!(release.tag_name.clone()));
+        body.insert("target_commitish", json!(target_commit));
+        body.insert(
+            "generate_release_notes",
+            json!(release.generate_release_notes),
+        );
+
+        http_service::post(url, Some(self.headers()), Some(body))
+    }
+
+    fn release_description(&self, tag_name: &str) -> Result<String, Box<dyn Error>> {
+        let url = format!(
+            "{}/repos/{}/releases/tags/{}",
+            self.config.api_url, self.config.repository, tag_name
+        );
+
+        let parsed = http_service::get(url, Some(self.headers()), None)?;
+        Ok(parsed
+            .get("body")
+            .and_then(Value::as_str)
+            .unwrap_or("")
+            .to_string())
+    }
+
+    fn release_tag_names(&self) -> Result<Vec<String>, Box<dyn Error>> {
+        let url = format!(
+            "{}/repos/{}/releases",
+            self.config.api_url, self.config.repository
+        );
+        let query = HashMap::from([("per_page", "100")]);
+
+        let pages = http_service::get_pages(url, Some(self.headers()), Some(query))?;
+        Ok(forges::tag_names_of(pages))
+    }
+
+    fn failed_checks(&self, commit_sha: &str) -> Result<Vec<String>, Box<dyn Error>> {
+        let commit_url = format!(
+            "{}/repos/{}/commits/{}",
+            self.config.api_url, self.config.repository, commit_sha
+        );
+        let query = HashMap::from([("per_page", "100")]);
+
+        // Checks of GitHub Actions and apps, then statuses of external CIs
+        let parsed = http_service::get(
+            format!("{}/check-runs", commit_url),
+            Some(self.headers()),
+            Some(query),
+        )?;
+        let check_runs = parsed.get("check_runs").and_then(Value::as_array);
+        let mut failed = forges::failed_names(
+            check_runs.map_or(&[], Vec::as_slice),
+            "name",
+            "conclusion",
+            &["failure", "cancelled", "timed_out", "action_required"],
+        );
+
+        let parsed =
+            http_service::get(format!("{}/status", commit_url), Some(self.headers()), None)?;
+        let statuses = parsed.get("statuses").and_then(Value::as_array);
+        failed.extend(forges::failed_names(
+            statuses.map_or(&[], Vec::as_slice),
+            "context",
+            "state",
+            &["failure", "error"],
+        ));
+
+        Ok(failed)
+    }
+
+    fn delete_release(&self, tag_name: &str) -> Result<bool, Box<dyn Error>> {
+        let releases_url = format!(
+            "{}/repos/{}/releases",
+            self.config.api_url, self.config.repository
+        );
+
+        // Releases are deleted by id only
+        let parsed = match http_service::get_optional(
+            format!("{}/tags/{}", releases_url, tag_name),
+            Some(self.headers()),
+        )? {
+            Some(parsed) => parsed,
+            None => return Ok(false),
+        };
+        let id = parsed.get("id").and_then(Value::as_u64).ok_or_else(|| {
+            Box::new(DefaultError {
+                message: format!("Release of {} has no id", tag_name),
+                source: None,
+            })
+        })?;
+
+        http_service::delete(format!("{}/{}", releases_url, id), Some(self.headers()))
+    }
+}
+
+impl Github {
+    fn headers(&self) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert("User-Agent", "Semver-CI".parse().unwrap());
+        headers.insert("Accept", "application/vnd.github+json".parse().unwrap());
+        headers.insert(
+            "Authorization",
+            format!("Bearer {}", self.config.token()).parse().unwrap(),
+        );
+        headers
+    }
+}
@@ -0,0 +1,212 @@
+use crate::forges::{self, Forge, ForgeConfig};
+use crate::http_service;
+use crate::release::Release;
+use log::error;
+use reqwest::header::HeaderMap;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::error::Error;
+
+pub(crate) struct Gitlab {
+    pub(crate) config: ForgeConfig,
+}
+
+const IGNORE_CHANGE_PREFIXES: [&str; 5] = ["refactor:", "style:", "test:", "chore:", "Merge "];
+
+impl Forge for Gitlab {
+    fn name(&self) -> String {
+        "GitLab".to_string()
+    }
+
+    fn create_release(
+        &self,
+        release: &Release,
+        target_commit: &str,
+    ) -> Result<HashMap<String, Value>, Box<dyn Error>> {
+        let url = format!("{}/releases", self.project_api_url());
+
+        let description = self.release_notes(
+            release.description.clone(),
+            release.generate_release_notes,
+            &release.previous_tag,
+            target_commit,
+        );
+
+        let mut body = HashMap::new();
+        body.insert("name", json!(release.name.clone()));
+        body.insert("description", json!(description));
+        body.insert("tag_name", json!(release.tag_name.clone()));
+        body.insert("tag_message", json!(release.tag_message.clone()));
+        body.insert("ref", json!(target_commit));
+
+        http_service::post(url, Some(self.headers()), Some(body))
+    }
+
+    fn release_description(&self, tag_name: &str) -> Result<String, Box<dyn Error>> {
+        let url = format!(
+            "{}/releases/{}",
+            self.project_api_url(),
+            tag_name.replace('/', "%2F")
+        );
+
+        let parsed = http_service::get(url, Some(self.headers()), None)?;
+        Ok(parsed
+            .get("description")
+            .and_then(Value::as_str)
+            .unwrap_or("")
+            .to_string())
+    }
+
+    fn release_tag_names(&self) -> Result<Vec<String>, Box<dyn Error>> {
+        let url = format!("{}/releases", self.project_api_url());
+        let query = HashMap::from([("per_page", "100")]);
+
+        let pages = http_service::get_pages(url, Some(self.headers()), Some(query))?;
+        Ok(forges::tag_names_of(pages))
+    }
+
+    fn failed_checks(&self, commit_sha: &str) -> Result<Vec<String>, Box<dyn Error>> {
+        let url = format!(
+            "{}/repository/commits/{}/statuses",
+            self.project_api_url(),
+            commit_sha
+        );
+        let query = HashMap::from([("per_page", "100")]);
+
+        let statuses = http_service::get_list(url, Some(self.headers()), Some(query))?;
+        let required: Vec<Value> = statuses
+            .into_iter()
+            .filter(|status| status.get("allow_failure") != Some(&Value::Bool(true)))
+            .collect();
+        Ok(forges::failed_names(
+            &required,
+            "name",
+            "status",
+            &["failed", "canceled"],
+        ))
+    }
+
+    fn delete_release(&self, tag_name: &str) -> Result<bool, Box<dyn Error>> {
+        let url = format!(
+            "{}/releases/{}",
+            self.project_api_url(),
+            tag_name.replace('/', "%2F")
+        );
+
+        http_service::delete(url, Some(self.headers()))
+    }
+}
+
+impl Gitlab {
+    fn project_api_url(&self) -> String {
+        format!(
+            "{}/projects/{}",
+            self.config.api_url,
+            self.config.repository.replace('/', "%2F")
+        )
+    }
+
+    // CI_JOB_TOKEN is only accepted in the JOB-TOKEN header, any other token is a personal/project token
+    fn headers(&self) -> HeaderMap {
+        let header_name =
+            if self.config.token_env == "CI_JOB_TOKEN" && self.config.is_token_from_env() {
+                "JOB-TOKEN"
+            } else {
+                "PRIVATE-TOKEN"
+            };
+
+        let mut headers = HeaderMap::new();
+        headers.insert(header_name, self.config.token().parse().unwrap());
+        headers
+    }
+
+    fn release_notes(&self, prepend: String, auto_generate: bool, from: &str, to: &str) -> String {
+        let mut notes = prepend.clone();
+
+        if auto_generate {
+            if from == "v0.0.0" {
+                notes += &self.commits(to);
+            } else {
+                notes += &self.compare(from, to).unwrap_or_else(|e| {
+                    error!("{}", e);
+                    self.web_compare_url(from, to)
+                })
+            }
+        }
+
+        notes
+    }
+
+    fn commits(&self, ref_name: &str) -> String {
+        let commits = format!("{}/-/commits/{}", self.config.project_url, ref_name);
+
+        format!(r#"Full Changelog: {}"#, commits)
+    }
+
+    fn web_compare_url(&self, from: &str, to: &str) -> String {
+        format!(
+            r#"# What's Changed
+{}/-/compare/{}...{}"#,
+            self.config.project_url, from, to
+        )
+    }
+
+    fn compare(&self, from: &str, to: &str) -> Result<String, Box<dyn Error>> {
+        let url = format!("{}/repository/compare", self.project_api_url());
+
+        let mut query = HashMap::new();
+        query.insert("from", from);
+        query.insert("to", to);
+
+        let parsed = http_service::get(url, Some(self.headers()), Some(query))?;
+        let commits = self.collect_commits(&parsed);
+        let empty_string_value = Value::String("".to_string());
+        let full_diff = parsed
+            .get("web_url")
+            .unwrap_or(&empty_string_value)
+            .as_str()
+            .unwrap_or("");
+
+        Ok(format!(
+            r#"## What's Changed
+{}
+
+Full Changelog: {}"#,
+            commits, full_diff
+        ))
+    }
+
+    fn collect_commits(&self, compare_response: &HashMap<String, Value>) -> String {
+        let empty_string_value = Value::String("".to_string());
+
+        compare_response
+            .get("commits")
+            .unwrap_or(&Value::Array(vec![]))
+            .as_array()
+            .unwrap_or(&Vec::new())
+            .iter()
+            .filter_map(|object| {
+                let message = object
+                    .get("message")
+                    .unwrap_or(&empty_string_value)
+                    .as_str()
+                    .unwrap_or("");
+                let web_url = object
+                    .get("web_url")
+                    .unwrap_or(&empty_string_value)
+                    .as_str()
+                    .unwrap_or("");
+                if (message.is_empty() && web_url.is_empty())
+                    || IGNORE_CHANGE_PREFIXES
+                        .iter()
+                        .any(|&prefix| message.starts_with(prefix))
+                {
+                    None
+                } else {
+                    Some(format!("* [{}]({})", message, web_url))
+                }
+            })
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+}
@@ -1,57 +1,196 @@
 use crate::semantic_version::SemanticVersion;
-use git2::string_array::StringArray;
+use crate::tag_template::TagTemplate;
 use git2::{
-    Config, Cred, CredentialType, Error, FetchOptions, ObjectType, Oid, PushOptions,
-    RemoteCallbacks, Repository,
+    Branch, BranchType, Config, Cred, CredentialType, Direction, Error, ErrorClass, ErrorCode,
+    FetchOptions, ObjectType, Oid, PushOptions, RemoteCallbacks, Repository, Sort, Status,
+    StatusOptions,
 };
-use log::error;
+use log::{info, warn};
 use regex::Regex;
+use std::collections::HashMap;
 use std::env;
+use std::fmt;
 use std::ops::Not;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+const SHALLOW_FETCH_DEPTH: i32 = 50;
 
 pub(crate) fn tag_names(
     repo_path: &str,
     force_fetch_tags: bool,
+    remotes: &[String],
     git_username: &str,
     git_token: &str,
-) -> Result<StringArray, Error> {
+) -> Result<Vec<String>, Error> {
     let repo = Repository::open(repo_path)?;
 
     if force_fetch_tags {
-        fetch_refs(&repo, git_username, git_token, &["refs/tags/*:refs/tags/*"])?;
+        for remote in remotes {
+            let refspecs = ["refs/tags/*:refs/tags/*"];
+            match repo.is_shallow() {
+                true => git_fetch(&repo, remote, git_username, git_token, &[], &refspecs)?,
+                false => fetch_refs(&repo, remote, git_username, git_token, &refspecs)?,
+            }
+        }
     }
 
-    repo.tag_names(None)
+    Ok(repo
+        .tag_names(None)?
+        .iter()
+        .flatten()
+        .map(str::to_string)
+        .collect())
 }
 
-pub(crate) fn last_tag_by_pattern(
-    tag_names: &StringArray,
-    tag_pattern: &str,
-    default: Option<SemanticVersion>,
-) -> Option<SemanticVersion> {
-    let tag_regex = Regex::new(tag_pattern).unwrap();
-    let mut valid_versions: Vec<SemanticVersion> = vec![];
+/// Deepens a shallow clone until an official version tag is reachable from HEAD.
+/// The history is deepened by a doubling number of commits, up to `max_depth` commits.
+pub(crate) fn deepen_until_tag_reachable(
+    repo_path: &str,
+    remote: &str,
+    git_username: &str,
+    git_token: &str,
+    template: &TagTemplate,
+    max_depth: i32,
+) -> Result<(), Error> {
+    let mut repo = Repository::open(repo_path)?;
+    if !repo.is_shallow() || max_depth <= 0 {
+        return Ok(());
+    }
 
-    for tag_name in tag_names.iter().flatten() {
-        if tag_regex.is_match(tag_name).not() {
-            continue;
-        }
+    let has_version_tags = repo
+        .tag_names(None)?
+        .iter()
+        .flatten()
+        .any(|tag_name| is_official_tag(template, tag_name));
+    if !has_version_tags {
+        info!("Shallow repository without version tags. Nothing to deepen");
+        return Ok(());
+    }
 
-        match SemanticVersion::from_string(tag_name.to_string()) {
-            Ok(version) => valid_versions.push(version),
-            Err(msg) => error!("{}", msg),
+    let mut deepened = 0;
+    let mut depth = SHALLOW_FETCH_DEPTH;
+    loop {
+        if let Some(tag_name) = reachable_tag(&repo, template)? {
+            info!("Shallow repository: {} is reachable from HEAD", tag_name);
+            return Ok(());
         }
+        if !repo.is_shallow() {
+            return Ok(());
+        }
+        if deepened >= max_depth {
+            warn!(
+                "Shallow repository: no version tag reachable within {} more commits. Versions may be computed from unreachable tags",
+                max_depth
+            );
+            return Ok(());
+        }
+
+        depth = depth.min(max_depth - deepened);
+        info!(
+            "Shallow repository: deepening history by {} commits from {}",
+            depth, remote
+        );
+        let deepen = format!("--deepen={}", depth);
+        git_fetch(&repo, remote, git_username, git_token, &[&deepen], &[])?;
+
+        // Reopen to pick up the new shallow roots
+        repo = Repository::open(repo_path)?;
+        deepened += depth;
+        depth *= 2;
     }
+}
 
-    if valid_versions.is_empty() {
-        default
-    } else {
-        valid_versions.sort_by(|a, b| b.cmp(a));
-        Some(valid_versions[0].clone())
+/// Runs `git fetch` in a shallow repository. libgit2 replaces .git/shallow with the shallow
+/// roots of its last fetch, which leaves the parents of the other roots missing.
+fn git_fetch(
+    repo: &Repository,
+    remote: &str,
+    user: &str,
+    token: &str,
+    options: &[&str],
+    refspecs: &[&str],
+) -> Result<(), Error> {
+    let mut command = Command::new("git");
+    command.current_dir(repo.workdir().unwrap_or(repo.path()));
+    if !token.is_empty() {
+        command
+            .arg("-c")
+            .arg("credential.helper=!f() { echo username=$SVCI_GIT_USERNAME; echo password=$SVCI_GIT_TOKEN; }; f")
+            .env("SVCI_GIT_USERNAME", if user.is_empty() { "git" } else { user })
+            .env("SVCI_GIT_TOKEN", token);
+    }
+    let output = command
+        .args(["fetch", "--quiet"])
+        .args(options)
+        .arg(remote)
+        .args(refspecs)
+        .output()
+        .map_err(|e| Error::from_str(&format!("Failed to run git fetch: {}", e)))?;
+
+    match output.status.success() {
+        true => Ok(()),
+        false => Err(Error::from_str(&format!(
+            "git fetch failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ))),
     }
 }
 
+fn is_official_tag(template: &TagTemplate, tag_name: &str) -> bool {
+    template
+        .parse(tag_name)
+        .is_some_and(|version| version.prerelease_stage.is_empty())
+}
+
+fn reachable_tag(repo: &Repository, template: &TagTemplate) -> Result<Option<String>, Error> {
+    let head = repo.head()?.peel_to_commit()?.id();
+
+    for tag_name in repo.tag_names(None)?.iter().flatten() {
+        if is_official_tag(template, tag_name).not() {
+            continue;
+        }
+        let target = match repo.revparse_single(&format!("refs/tags/{}^{{commit}}", tag_name)) {
+            Ok(object) => object.id(),
+            Err(_) => continue,
+        };
+        if target == head || repo.graph_descendant_of(head, target).unwrap_or(false) {
+            return Ok(Some(tag_name.to_string()));
+        }
+    }
+
+    Ok(None)
+}
+
+/// Highest version of the tags named after `template` whose versions pass `filter`
+pub(crate) fn last_tag_by_template(
+    tag_names: &[String],
+    template: &TagTemplate,
+    filter: impl Fn(&SemanticVersion) -> bool,
+    default: Option<SemanticVersion>,
+) -> Option<SemanticVersion> {
+    tags_by_template(tag_names, template)
+        .into_iter()
+        .map(|(_, version)| version)
+        .filter(filter)
+        .last()
+        .or(default)
+}
+
+/// Tags named after `template` with their versions, lowest precedence first
+pub(crate) fn tags_by_template(
+    tag_names: &[String],
+    template: &TagTemplate,
+) -> Vec<(String, SemanticVersion)> {
+    let mut tags: Vec<(String, SemanticVersion)> = tag_names
+        .iter()
+        .filter_map(|tag_name| Some((tag_name.clone(), template.parse(tag_name)?)))
+        .collect();
+
+    tags.sort_by(|(a_name, a), (b_name, b)| a.cmp(b).then_with(|| a_name.cmp(b_name)));
+    tags
+}
+
 pub(crate) fn branch_name(repo_path: &str) -> Result<String, Error> {
     let repo = Repository::open(repo_path)?;
 
@@ -70,11 +209,182 @@ pub(crate) fn branch_name(repo_path: &str) -> Result<String, Error> {
     }
 }
 
-pub(crate) fn short_commit_sha(repo_path: &str) -> Result<String, Error> {
+/// Branch of a detached HEAD: a branch whose tip is HEAD, otherwise the closest branch
+/// containing HEAD. Candidates are ordered by the first matching `preference`, then by
+/// distance and name. Returns the branch name and how it was found.
+pub(crate) fn detached_head_branch_name(
+    repo_path: &str,
+    preference: &[Regex],
+) -> Result<(String, String), Error> {
+    let repo = Repository::open(repo_path)?;
+    let head = repo.head()?.peel_to_commit()?.id();
+
+    // (preference rank, commits ahead of HEAD, branch name, ref shorthand)
+    let mut tips = vec![];
+    let mut containing = vec![];
+    for branch in repo.branches(None)? {
+        let (branch, branch_type) = branch?;
+        let reference = branch.get();
+        let (Some(shorthand), Some(refname)) = (reference.shorthand(), reference.name()) else {
+            continue;
+        };
+        if reference.symbolic_target().is_some() {
+            continue;
+        }
+        let Ok(tip) = reference.peel_to_commit().map(|commit| commit.id()) else {
+            continue;
+        };
+        let name = match branch_type {
+            BranchType::Local => shorthand.to_string(),
+            BranchType::Remote => {
+                let remote = repo.branch_remote_name(refname)?;
+                let remote = remote.as_str().unwrap_or_default();
+                shorthand
+                    .strip_prefix(&format!("{}/", remote))
+                    .unwrap_or(shorthand)
+                    .to_string()
+            }
+        };
+        if name == "HEAD" {
+            continue;
+        }
+
+        let rank = preference
+            .iter()
+            .position(|regex| regex.is_match(&name))
+            .unwrap_or(preference.len());
+        if tip == head {
+            tips.push((rank, 0, name, shorthand.to_string()));
+        } else if repo.graph_descendant_of(tip, head)? {
+            let (ahead, _) = repo.graph_ahead_behind(tip, head)?;
+            containing.push((rank, ahead, name, shorthand.to_string()));
+        }
+    }
+    tips.sort();
+    containing.sort();
+
+    if let Some((_, _, name, shorthand)) = tips.into_iter().next() {
+        return Ok((name, format!("tip of {} is HEAD", shorthand)));
+    }
+    if let Some((_, ahead, name, shorthand)) = containing.into_iter().next() {
+        return Ok((
+            name,
+            format!("{} contains HEAD, {} commits ahead", shorthand, ahead),
+        ));
+    }
+    Err(Error::from_str(
+        "HEAD is in detached state and no branch contains it. Set --branch or SVCI_BRANCH",
+    ))
+}
+
+pub(crate) fn commit_sha(repo_path: &str) -> Result<String, Error> {
     let repo = Repository::open(repo_path)?;
 
     let commit_sha = repo.head()?.peel_to_commit()?.id().to_string();
 
+    Ok(commit_sha)
+}
+
+/// Committer time of HEAD: seconds since the epoch and offset in minutes
+pub(crate) fn commit_time(repo_path: &str) -> Result<(i64, i32), Error> {
+    let repo = Repository::open(repo_path)?;
+
+    let time = repo.head()?.peel_to_commit()?.committer().when();
+
+    Ok((time.seconds(), time.offset_minutes()))
+}
+
+/// Tagger time of an annotated tag or committer time of a lightweight tag's commit,
+/// in seconds since the epoch
+pub(crate) fn tag_time(repo: &Repository, tag_name: &str) -> Result<i64, Error> {
+    let object = repo.revparse_single(&format!("refs/tags/{}", tag_name))?;
+
+    let time = match object.as_tag().and_then(|tag| tag.tagger()) {
+        Some(tagger) => tagger.when(),
+        None => object.peel_to_commit()?.committer().when(),
+    };
+
+    Ok(time.seconds())
+}
+
+/// Whether `tag_name` is a tag of the repository, not only e.g. a forge release
+pub(crate) fn has_tag(repo_path: &str, tag_name: &str) -> bool {
+    Repository::open(repo_path).is_ok_and(|repo| {
+        repo.revparse_single(&format!("refs/tags/{}^{{commit}}", tag_name))
+            .is_ok()
+    })
+}
+
+/// Number of commits reachable from HEAD but not from the commit of `tag_name`,
+/// or of all commits reachable from HEAD without a tag
+pub(crate) fn commits_since(repo_path: &str, tag_name: Option<&str>) -> Result<u64, Error> {
+    let repo = Repository::open(repo_path)?;
+
+    let mut revwalk = repo.revwalk()?;
+    revwalk.push_head()?;
+    if let Some(tag_name) = tag_name {
+        let target = repo.revparse_single(&format!("refs/tags/{}^{{commit}}", tag_name))?;
+        revwalk.hide(target.id())?;
+    }
+
+    Ok(revwalk.count() as u64)
+}
+
+/// SHA and message of the commits of a revision range, e.g. `v1.2.3..HEAD`, newest first.
+/// Merge commits are left out
+pub(crate) fn commit_messages(
+    repo_path: &str,
+    range: &str,
+) -> Result<Vec<(String, String)>, Error> {
+    let repo = Repository::open(repo_path)?;
+
+    let mut revwalk = repo.revwalk()?;
+    match range.contains("..") {
+        true => revwalk.push_range(range)?,
+        false => revwalk.push(repo.revparse_single(range)?.peel_to_commit()?.id())?,
+    }
+
+    let mut messages = vec![];
+    for oid in revwalk {
+        let commit = repo.find_commit(oid?)?;
+        if commit.parent_count() > 1 {
+            continue;
+        }
+        let message = String::from_utf8_lossy(commit.message_bytes()).to_string();
+        messages.push((commit.id().to_string(), message));
+    }
+
+    Ok(messages)
+}
+
+/// The first of `tag_names` found walking back from HEAD, like `git describe`
+pub(crate) fn last_reachable_tag(
+    repo_path: &str,
+    tag_names: &[String],
+) -> Result<Option<String>, Error> {
+    let repo = Repository::open(repo_path)?;
+
+    let mut tagged_commits = HashMap::new();
+    for tag_name in tag_names {
+        let target = repo.revparse_single(&format!("refs/tags/{}^{{commit}}", tag_name))?;
+        tagged_commits.entry(target.id()).or_insert(tag_name);
+    }
+
+    let mut revwalk = repo.revwalk()?;
+    revwalk.set_sorting(Sort::TOPOLOGICAL | Sort::TIME)?;
+    revwalk.push_head()?;
+    for oid in revwalk {
+        if let Some(tag_name) = tagged_commits.get(&oid?) {
+            return Ok(Some(tag_name.to_string()));
+        }
+    }
+
+    Ok(None)
+}
+
+pub(crate) fn short_commit_sha(repo_path: &str) -> Result<String, Error> {
+    let commit_sha = commit_sha(repo_path)?;
+
     Ok(commit_sha[..8].to_string())
 }
 
@@ -97,6 +407,18 @@ pub(crate) fn get_config_value(repo_path: &str, name: &str) -> Option<String> {
     value
 }
 
+pub(crate) fn credential_helper_password(repo_path: &str, url: &str) -> Option<String> {
+    let config = match Repository::open(repo_path) {
+        Ok(repo) => repo.config().ok()?,
+        Err(_) => Config::open_default().ok()?,
+    };
+
+    git2::CredentialHelper::new(url)
+        .config(&config)
+        .execute()
+        .map(|(_username, password)| password)
+}
+
 pub(crate) fn set_config_value(repo_path: &str, name: &str, value: &str) -> Result<(), Error> {
     let repo = Repository::open(repo_path)?;
 
@@ -118,7 +440,8 @@ pub(crate) fn clone(
 ) -> Result<Repository, Error> {
     let mut fetch_options = FetchOptions::new();
     let mut callbacks = RemoteCallbacks::new();
-    callbacks.credentials(|_url, username, cred| git_auth_callback(cred, username, user, token));
+    let mut auth = GitAuth::new(None, user, token);
+    callbacks.credentials(move |url, username, allowed| auth.credentials(url, username, allowed));
 
     fetch_options.remote_callbacks(callbacks);
     fetch_options.depth(depth);
@@ -136,17 +459,19 @@ pub(crate) fn checkout(repo: &Repository, ref_name: &str) -> Result<(), Error> {
 
 pub(crate) fn fetch_refs(
     repo: &Repository,
+    remote: &str,
     user: &str,
     token: &str,
     refspecs: &[&str],
 ) -> Result<(), Error> {
     let mut fetch_options = FetchOptions::new();
     let mut callbacks = RemoteCallbacks::new();
-    callbacks.credentials(|_url, username, cred| git_auth_callback(cred, username, user, token));
+    let mut auth = GitAuth::new(Some(repo), user, token);
+    callbacks.credentials(move |url, username, allowed| auth.credentials(url, username, allowed));
 
     fetch_options.remote_callbacks(callbacks);
 
-    repo.find_remote("origin")?
+    repo.find_remote(remote)?
         .fetch(refspecs, Some(&mut fetch_options), None)
 }
 
@@ -164,47 +489,336 @@ pub(crate) fn tag(
     repo.tag(tag_name, &git_object, &tagger, tag_message, false)
 }
 
+/// Tags `commit_id` instead of HEAD
+pub(crate) fn tag_commit(
+    repo: &Repository,
+    tag_name: &str,
+    commit_id: Oid,
+    tag_message: &str,
+    user: &str,
+    email: &str,
+) -> Result<Oid, Error> {
+    let git_object = repo.find_object(commit_id, Some(ObjectType::Commit))?;
+    let tagger = git2::Signature::now(user, email)?;
+
+    repo.tag(tag_name, &git_object, &tagger, tag_message, false)
+}
+
+/// Local branch `name`, created from `<remote>/<name>` if it only exists there
+pub(crate) fn local_branch<'a>(
+    repo: &'a Repository,
+    name: &str,
+    remote: &str,
+) -> Result<Branch<'a>, Error> {
+    match repo.find_branch(name, BranchType::Local) {
+        Err(e) if e.code() == ErrorCode::NotFound => {
+            let remote_branch =
+                repo.find_branch(&format!("{}/{}", remote, name), BranchType::Remote)?;
+            let commit = remote_branch.get().peel_to_commit()?;
+            repo.branch(name, &commit, false)
+        }
+        result => result,
+    }
+}
+
+/// Creates the branch `name` at `commit_id` and checks it out
+pub(crate) fn create_branch(repo: &Repository, name: &str, commit_id: Oid) -> Result<(), Error> {
+    let commit = repo.find_commit(commit_id)?;
+    let branch = repo.branch(name, &commit, false)?;
+    let ref_name = branch.get().name().unwrap_or_default().to_string();
+
+    repo.checkout_tree(commit.as_object(), None)?;
+    repo.set_head(&ref_name)
+}
+
+/// Whether merging `commit_id` into the local branch `target` has conflicts. Writes nothing
+pub(crate) fn has_merge_conflicts(
+    repo: &Repository,
+    target: &str,
+    commit_id: Oid,
+) -> Result<bool, Error> {
+    let ours = repo
+        .find_branch(target, BranchType::Local)?
+        .get()
+        .peel_to_commit()?;
+    let theirs = repo.find_commit(commit_id)?;
+
+    Ok(repo.merge_commits(&ours, &theirs, None)?.has_conflicts())
+}
+
+/// Merges `commit_id` into the local branch `target` with a merge commit, like `git merge --no-ff`.
+/// The working tree is updated if `target` is checked out.
+pub(crate) fn merge_no_ff(
+    repo: &Repository,
+    target: &str,
+    commit_id: Oid,
+    message: &str,
+    user: &str,
+    email: &str,
+) -> Result<Oid, Error> {
+    let mut target_ref = repo
+        .find_branch(target, BranchType::Local)?
+        .into_reference();
+    let ours = target_ref.peel_to_commit()?;
+    let theirs = repo.find_commit(commit_id)?;
+
+    let mut index = repo.merge_commits(&ours, &theirs, None)?;
+    if index.has_conflicts() {
+        return Err(Error::from_str(&format!(
+            "Merging {} into {} has conflicts",
+            commit_id, target
+        )));
+    }
+    let tree = repo.find_tree(index.write_tree_to(repo)?)?;
+    let signature = git2::Signature::now(user, email)?;
+    let merge_id = repo.commit(
+        None,
+        &signature,
+        &signature,
+        message,
+        &tree,
+        &[&ours, &theirs],
+    )?;
+
+    if repo.head()?.name() == target_ref.name() {
+        repo.checkout_tree(tree.as_object(), None)?;
+    }
+    target_ref.set_target(merge_id, message)?;
+
+    Ok(merge_id)
+}
+
+/// Commit a tag points to, through annotated tags
+pub(crate) fn tagged_commit(repo: &Repository, tag_name: &str) -> Result<Oid, Error> {
+    let reference = repo.find_reference(&format!("refs/tags/{}", tag_name))?;
+    Ok(reference.peel_to_commit()?.id())
+}
+
 pub(crate) fn push_tag(
     repo: &Repository,
+    remote: &str,
     user: &str,
     token: &str,
     tag_name: &str,
 ) -> Result<(), Error> {
+    let ref_name = format!("refs/tags/{}", tag_name);
+    push_refs(repo, remote, user, token, &[ref_name])
+}
+
+/// Pushes full ref names, e.g. `refs/heads/main`, in a single push
+pub(crate) fn push_refs(
+    repo: &Repository,
+    remote: &str,
+    user: &str,
+    token: &str,
+    ref_names: &[String],
+) -> Result<(), Error> {
+    // The remote rejects refs per update, which libgit2 only reports through a callback
+    let mut rejected: Option<String> = None;
+
     let mut push_options = PushOptions::new();
     let mut callbacks = RemoteCallbacks::new();
-    callbacks.credentials(|_url, username, cred| git_auth_callback(cred, username, user, token));
+    let mut auth = GitAuth::new(Some(repo), user, token);
+    callbacks.credentials(move |url, username, allowed| auth.credentials(url, username, allowed));
+    callbacks.push_update_reference(|refname, status| {
+        if let Some(message) = status {
+            rejected = Some(format!("{} rejected: {}", refname, message));
+        }
+        Ok(())
+    });
 
     push_options.remote_callbacks(callbacks);
 
-    let ref_spec = format!("refs/tags/{}", tag_name);
-    repo.find_remote("origin")?
-        .push(&[ref_spec], Some(&mut push_options))
+    repo.find_remote(remote)?
+        .push(ref_names, Some(&mut push_options))?;
+    drop(push_options);
+
+    match rejected {
+        Some(message) => Err(Error::from_str(&message)),
+        None => Ok(()),
+    }
 }
 
-fn git_auth_callback(
-    cred: CredentialType,
-    username: Option<&str>,
+/// Refs of `remote` with their commit ids, like `git ls-remote`
+pub(crate) fn remote_refs(
+    repo: &Repository,
+    remote: &str,
     user: &str,
     token: &str,
-) -> Result<Cred, Error> {
-    if cred.is_ssh_key() {
-        let ssh_username = username.unwrap_or(user);
-        Cred::ssh_key(
-            ssh_username,
-            None,
-            Path::new(&ssh_key_path()),
-            ssh_key_passphrase().as_deref(),
-        )
-    } else if cred.is_user_pass_plaintext() {
-        let plain_username = username.unwrap_or(user);
-        Cred::userpass_plaintext(plain_username, token)
-    } else {
-        panic!("Unexpected CredentialType: {:?}", cred)
+) -> Result<Vec<(String, Oid)>, Error> {
+    let mut callbacks = RemoteCallbacks::new();
+    let mut auth = GitAuth::new(Some(repo), user, token);
+    callbacks.credentials(move |url, username, allowed| auth.credentials(url, username, allowed));
+
+    let mut remote = repo.find_remote(remote)?;
+    let connection = remote.connect_auth(Direction::Fetch, Some(callbacks), None)?;
+
+    Ok(connection
+        .list()?
+        .iter()
+        .map(|head| (head.name().to_string(), head.oid()))
+        .collect())
+}
+
+/// Paths with staged or unstaged changes, untracked files aside
+pub(crate) fn changed_paths(repo: &Repository) -> Result<Vec<String>, Error> {
+    let mut status_options = StatusOptions::new();
+    status_options
+        .include_untracked(false)
+        .include_ignored(false);
+
+    Ok(repo
+        .statuses(Some(&mut status_options))?
+        .iter()
+        .filter(|entry| entry.status() != Status::CURRENT)
+        .filter_map(|entry| entry.path().map(str::to_string))
+        .collect())
+}
+
+#[derive(Clone, PartialEq)]
+enum AuthMethod {
+    SshKeyFile(PathBuf),
+    SshAgent,
+    CredentialHelper,
+    Token,
+}
+
+impl fmt::Display for AuthMethod {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AuthMethod::SshKeyFile(path) => write!(f, "ssh key {}", path.display()),
+            AuthMethod::SshAgent => write!(f, "ssh-agent"),
+            AuthMethod::CredentialHelper => write!(f, "credential.helper"),
+            AuthMethod::Token => write!(f, "token"),
+        }
+    }
+}
+
+/// Credentials for git remotes. libgit2 calls back after every rejected credential,
+/// so each call hands out the next method that has not been tried yet.
+struct GitAuth {
+    config: Option<Config>,
+    user: String,
+    token: String,
+    tried: Vec<AuthMethod>,
+}
+
+impl GitAuth {
+    fn new(repo: Option<&Repository>, user: &str, token: &str) -> Self {
+        let config = match repo {
+            Some(repo) => repo.config().ok(),
+            None => Config::open_default().ok(),
+        };
+
+        GitAuth {
+            config,
+            user: user.to_string(),
+            token: token.to_string(),
+            tried: vec![],
+        }
+    }
+
+    fn credentials(
+        &mut self,
+        url: &str,
+        username: Option<&str>,
+        allowed: CredentialType,
+    ) -> Result<Cred, Error> {
+        let username = match username {
+            Some(username) => username.to_string(),
+            None if self.user.is_empty() => "git".to_string(),
+            None => self.user.clone(),
+        };
+
+        if allowed.contains(CredentialType::USERNAME) {
+            return Cred::username(&username);
+        }
+
+        for method in self.methods(allowed) {
+            if self.tried.contains(&method) {
+                continue;
+            }
+            self.tried.push(method.clone());
+
+            match self.credential(&method, url, &username) {
+                Ok(cred) => {
+                    info!("Authenticating to {} with {}", url, method);
+                    return Ok(cred);
+                }
+                Err(e) => info!("Skip {}: {}", method, e.message()),
+            }
+        }
+
+        let tried = self
+            .tried
+            .iter()
+            .map(|method| method.to_string())
+            .collect::<Vec<String>>();
+        Err(Error::new(
+            ErrorCode::Auth,
+            ErrorClass::Net,
+            format!(
+                "Authentication failed for {} (allowed: {:?}, tried: [{}])",
+                url,
+                allowed,
+                tried.join(", ")
+            ),
+        ))
+    }
+
+    // ssh-agent, credential.helper, default ssh keys, token. GIT_SSH_KEY_PATH goes first when set.
+    fn methods(&self, allowed: CredentialType) -> Vec<AuthMethod> {
+        let mut methods = vec![];
+
+        if allowed.is_ssh_key() {
+            if let Ok(path) = env::var("GIT_SSH_KEY_PATH") {
+                methods.push(AuthMethod::SshKeyFile(PathBuf::from(path)));
+            }
+            if env::var("SSH_AUTH_SOCK").is_ok() {
+                methods.push(AuthMethod::SshAgent);
+            }
+        }
+        if allowed.is_user_pass_plaintext() && self.config.is_some() {
+            methods.push(AuthMethod::CredentialHelper);
+        }
+        if allowed.is_ssh_key() {
+            methods.extend(
+                default_ssh_key_paths()
+                    .into_iter()
+                    .filter(|path| path.exists())
+                    .map(AuthMethod::SshKeyFile),
+            );
+        }
+        if allowed.is_user_pass_plaintext() && !self.token.is_empty() {
+            methods.push(AuthMethod::Token);
+        }
+
+        methods
+    }
+
+    fn credential(&self, method: &AuthMethod, url: &str, username: &str) -> Result<Cred, Error> {
+        match method {
+            AuthMethod::SshKeyFile(path) => {
+                Cred::ssh_key(username, None, path, ssh_key_passphrase().as_deref())
+            }
+            AuthMethod::SshAgent => Cred::ssh_key_from_agent(username),
+            AuthMethod::CredentialHelper => match &self.config {
+                Some(config) => Cred::credential_helper(config, url, Some(username)),
+                None => Err(Error::from_str("no git config")),
+            },
+            AuthMethod::Token => Cred::userpass_plaintext(username, &self.token),
+        }
     }
 }
 
-fn ssh_key_path() -> String {
-    env::var("GIT_SSH_KEY_PATH").unwrap_or_else(|e| panic!("{}: \"GIT_SSH_KEY_PATH\"", e))
+fn default_ssh_key_paths() -> Vec<PathBuf> {
+    match env::var("HOME").or_else(|_| env::var("USERPROFILE")) {
+        Ok(home) => ["id_ed25519", "id_rsa"]
+            .iter()
+            .map(|name| Path::new(&home).join(".ssh").join(name))
+            .collect(),
+        Err(_) => vec![],
+    }
 }
 
 fn ssh_key_passphrase() -> Option<String> {
@@ -213,3 +827,31 @@ fn ssh_key_passphrase() -> Option<String> {
         Err(_e) => None,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn git_auth_tries_each_method_once_then_fails_with_auth_error() {
+        let mut auth = GitAuth {
+            config: None,
+            user: "user".to_string(),
+            token: "token".to_string(),
+            tried: vec![],
+        };
+        let url = "https://example.com/owner/repo.git";
+
+        assert!(auth
+            .credentials(url, None, CredentialType::USER_PASS_PLAINTEXT)
+            .is_ok());
+        assert!(auth.tried == vec![AuthMethod::Token]);
+
+        let error = match auth.credentials(url, None, CredentialType::USER_PASS_PLAINTEXT) {
+            Ok(_) => panic!("credentials must be exhausted"),
+            Err(e) => e,
+        };
+        assert_eq!(error.code(), ErrorCode::Auth);
+        assert!(error.message().contains("tried: [token]"));
+    }
+}
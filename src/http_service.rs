@@ -1,20 +1,39 @@
 use crate::config;
 use crate::default_error::DefaultError;
 use log::info;
-use reqwest::blocking::Response;
-use reqwest::header::HeaderMap;
+use reqwest::blocking::{Client, Response};
+use reqwest::header::{HeaderMap, LINK};
+use reqwest::{Certificate, StatusCode, Url};
+use serde::de::DeserializeOwned;
 use serde_json::Value;
 use std::collections::HashMap;
 use std::error::Error;
+use std::fmt::Debug;
+use std::fs;
 
-fn handle_response(response: Response) -> Result<HashMap<String, Value>, Box<dyn Error>> {
+fn client() -> Result<Client, Box<dyn Error>> {
+    let mut client_builder = Client::builder();
+    if let Some(path) = config::ca_bundle() {
+        let pem = fs::read(&path).map_err(|e| {
+            Box::new(DefaultError {
+                message: format!("Failed to read CA bundle: {}", path),
+                source: Some(Box::new(e)),
+            })
+        })?;
+        for certificate in Certificate::from_pem_bundle(&pem)? {
+            client_builder = client_builder.add_root_certificate(certificate);
+        }
+    }
+
+    Ok(client_builder.build()?)
+}
+
+fn handle_response<T: DeserializeOwned + Debug>(response: Response) -> Result<T, Box<dyn Error>> {
     let status = response.status();
     if status.is_success() {
         info!("{:#?}", response);
 
-        let parsed = response
-            .json::<HashMap<String, Value>>()
-            .unwrap_or_else(|e| panic!("{}", e));
+        let parsed = response.json::<T>().unwrap_or_else(|e| panic!("{}", e));
         info!("    body: {:#?}", parsed);
 
         Ok(parsed)
@@ -36,7 +55,7 @@ pub(crate) fn post(
     headers: Option<HeaderMap>,
     body: Option<HashMap<&str, Value>>,
 ) -> Result<HashMap<String, Value>, Box<dyn Error>> {
-    let mut request_builder = reqwest::blocking::Client::new().post(url);
+    let mut request_builder = client()?.post(url);
     if headers.is_some() {
         request_builder = request_builder.headers(headers.unwrap());
     }
@@ -61,7 +80,7 @@ pub(crate) fn get(
     headers: Option<HeaderMap>,
     query: Option<HashMap<&str, &str>>,
 ) -> Result<HashMap<String, Value>, Box<dyn Error>> {
-    let mut request_builder = reqwest::blocking::Client::new().get(url);
+    let mut request_builder = client()?.get(url);
     if headers.is_some() {
         request_builder = request_builder.headers(headers.unwrap());
     }
@@ -82,3 +101,177 @@ pub(crate) fn get(
 
     handle_response(response)
 }
+
+/// GET of a resource that may not exist. None if the endpoint answers with 404 Not Found
+pub(crate) fn get_optional(
+    url: String,
+    headers: Option<HeaderMap>,
+) -> Result<Option<HashMap<String, Value>>, Box<dyn Error>> {
+    let mut request_builder = client()?.get(url);
+    if headers.is_some() {
+        request_builder = request_builder.headers(headers.unwrap());
+    }
+
+    info!("{:#?}", request_builder);
+
+    if config::is_test() {
+        return Ok(None);
+    }
+
+    let response = request_builder.send()?;
+    if response.status() == StatusCode::NOT_FOUND {
+        return Ok(None);
+    }
+
+    handle_response(response).map(Some)
+}
+
+/// GET of an endpoint answering with a JSON array
+pub(crate) fn get_list(
+    url: String,
+    headers: Option<HeaderMap>,
+    query: Option<HashMap<&str, &str>>,
+) -> Result<Vec<Value>, Box<dyn Error>> {
+    let mut request_builder = client()?.get(url);
+    if headers.is_some() {
+        request_builder = request_builder.headers(headers.unwrap());
+    }
+    if query.is_some() {
+        request_builder = request_builder.query(&query.unwrap());
+    }
+
+    info!("{:#?}", request_builder);
+
+    if config::is_test() {
+        return Ok(vec![]);
+    }
+
+    let response = request_builder.send()?;
+
+    handle_response(response)
+}
+
+/// GET of every page of a paginated endpoint, following the `Link: <...>; rel="next"` header
+/// of each response. Returns the body of each page
+pub(crate) fn get_pages(
+    url: String,
+    headers: Option<HeaderMap>,
+    query: Option<HashMap<&str, &str>>,
+) -> Result<Vec<Value>, Box<dyn Error>> {
+    let client = client()?;
+    let mut request_builder = client.get(url);
+    if headers.is_some() {
+        request_builder = request_builder.headers(headers.clone().unwrap());
+    }
+    if query.is_some() {
+        request_builder = request_builder.query(&query.unwrap());
+    }
+
+    info!("{:#?}", request_builder);
+
+    if config::is_test() {
+        return Ok(vec![]);
+    }
+
+    let mut pages = vec![];
+    let mut response = request_builder.send()?;
+    loop {
+        let next_page = next_page_url(&response);
+        pages.push(handle_response(response)?);
+        let Some(next_page) = next_page else {
+            return Ok(pages);
+        };
+
+        let mut request_builder = client.get(next_page);
+        if headers.is_some() {
+            request_builder = request_builder.headers(headers.clone().unwrap());
+        }
+        info!("{:#?}", request_builder);
+        response = request_builder.send()?;
+    }
+}
+
+/// Target of the `rel="next"` link, resolved against the URL of `response`
+fn next_page_url(response: &Response) -> Option<Url> {
+    let links = response.headers().get(LINK)?.to_str().ok()?;
+    links.split(',').find_map(|link| {
+        let (target, params) = link.split_once(';')?;
+        params
+            .split(';')
+            .any(|param| matches!(param.trim(), r#"rel="next""# | "rel=next"))
+            .then(|| {
+                response
+                    .url()
+                    .join(target.trim().trim_matches(['<', '>']))
+                    .ok()
+            })
+            .flatten()
+    })
+}
+
+/// DELETE of a resource. Endpoints answer with an empty body. False if the resource doesn't
+/// exist, i.e. the endpoint answers with 404 Not Found
+pub(crate) fn delete(url: String, headers: Option<HeaderMap>) -> Result<bool, Box<dyn Error>> {
+    let mut request_builder = client()?.delete(url);
+    if headers.is_some() {
+        request_builder = request_builder.headers(headers.unwrap());
+    }
+
+    info!("{:#?}", request_builder);
+
+    if config::is_test() {
+        return Ok(true);
+    }
+
+    let response = request_builder.send()?;
+    let status = response.status();
+    if status.is_success() {
+        info!("{:#?}", response);
+        return Ok(true);
+    }
+    if status == StatusCode::NOT_FOUND {
+        return Ok(false);
+    }
+
+    let headers = response.headers().clone();
+    let body = response.text().unwrap();
+    Err(Box::new(DefaultError {
+        message: format!(
+            "Status: {}\nHeaders:\n{:#?}\nBody:\n{}",
+            status, headers, body
+        ),
+        source: None,
+    }))
+}
+
+pub(crate) fn upload(
+    url: String,
+    headers: Option<HeaderMap>,
+    field_name: &str,
+    file_path: &str,
+) -> Result<HashMap<String, Value>, Box<dyn Error>> {
+    let form = reqwest::blocking::multipart::Form::new()
+        .file(field_name.to_string(), file_path)
+        .map_err(|e| {
+            Box::new(DefaultError {
+                message: format!("Failed to read attachment: {}", file_path),
+                source: Some(Box::new(e)),
+            })
+        })?;
+
+    let mut request_builder = client()?.post(url).multipart(form);
+    if headers.is_some() {
+        request_builder = request_builder.headers(headers.unwrap());
+    }
+
+    info!("{:#?}", request_builder);
+    info!("    file: {}", file_path);
+
+    if config::is_test() {
+        return Ok(HashMap::new());
+    }
+
+    let response = request_builder.send()?;
+
+    handle_response(response)
+}
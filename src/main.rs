@@ -2,18 +2,25 @@ extern crate core;
 
 mod commands;
 mod config;
+mod conventional_commit;
 mod default_error;
+mod explanation;
+mod forges;
 mod git_service;
 mod http_service;
 mod pipelines;
 mod release;
 mod semantic_version;
+mod tag_template;
+mod version_scheme;
+mod version_sources;
 
 use dotenv::dotenv;
 
 fn main() {
     dotenv().ok();
-    env_logger::init();
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("warn")).init();
+    config::apply_ca_bundle();
 
     commands::run().unwrap_or_else(|e| match e.source() {
         Some(source) => panic!("{}\n    Caused by: {}", e, source),
@@ -1,9 +1,13 @@
 mod git_repo;
+mod gitea_actions;
 mod github_actions;
 mod gitlab_ci;
 
 use crate::default_error::DefaultError;
+use crate::forges;
+use crate::forges::{Forge, ForgeConfig};
 use crate::pipelines::git_repo::GitRepo;
+use crate::pipelines::gitea_actions::{GiteaActions, GITEA_ACTIONS};
 use crate::pipelines::github_actions::{GithubActions, GITHUB_ACTIONS};
 use crate::pipelines::gitlab_ci::{GitlabCI, GITLAB_CI};
 use crate::release::Release;
@@ -16,6 +20,7 @@ pub(crate) trait Pipeline {
     fn init(&self) {}
     fn name(&self) -> String;
     fn branch_name(&self) -> String;
+    fn commit_sha(&self) -> String;
     fn short_commit_sha(&self) -> String;
     fn git_username(&self) -> String;
     fn git_email(&self) -> String;
@@ -23,11 +28,28 @@ pub(crate) trait Pipeline {
     fn force_fetch_tags(&self) -> bool {
         true
     }
-    fn create_release(&self, _release: &Release) -> Result<HashMap<String, Value>, Box<dyn Error>> {
-        Err(Box::new(DefaultError {
+    fn forge_config(&self) -> Result<Option<ForgeConfig>, Box<dyn Error>> {
+        Ok(None)
+    }
+    fn pull_request(&self) -> Option<PullRequest> {
+        None
+    }
+    /// Tag the pipeline was triggered by
+    fn tag_name(&self) -> Option<String> {
+        None
+    }
+    fn forge(&self) -> Result<Box<dyn Forge>, Box<dyn Error>> {
+        let forge_config = self.forge_config()?.ok_or_else(|| DefaultError {
             message: format!("Not supported pipeline: {}", self.name()),
             source: None,
-        }))
+        })?;
+        forges::forge(forge_config)
+    }
+    fn create_release(&self, release: &Release) -> Result<HashMap<String, Value>, Box<dyn Error>> {
+        let forge = self.forge()?;
+        eprintln!("release on {}", forge.name());
+
+        forge.create_release(release, &self.commit_sha())
     }
 
     fn info(&self) -> PipelineInfo {
@@ -38,12 +60,19 @@ pub(crate) trait Pipeline {
             git_email: self.git_email(),
             git_token: self.git_token(),
             force_fetch_tags: self.force_fetch_tags(),
+            pull_request: self.pull_request(),
+            tag_name: self.tag_name(),
         }
     }
 }
 
 pub(crate) fn current_pipeline() -> &'static dyn Pipeline {
-    let pipeline = if env::var(GITHUB_ACTIONS).map_or(false, |v| v == "true") {
+    let github_actions = env::var(GITHUB_ACTIONS).map_or(false, |v| v == "true");
+    let pipeline = if env::var(GITEA_ACTIONS).map_or(false, |v| v == "true")
+        || (github_actions && gitea_actions::is_gitea_server())
+    {
+        &GiteaActions as &dyn Pipeline
+    } else if github_actions {
         &GithubActions as &dyn Pipeline
     } else if env::var(GITLAB_CI).map_or(false, |v| v == "true") {
         &GitlabCI as &dyn Pipeline
@@ -63,4 +92,13 @@ pub(crate) struct PipelineInfo {
     pub git_email: String,
     pub git_token: String,
     pub force_fetch_tags: bool,
+    pub pull_request: Option<PullRequest>,
+    pub tag_name: Option<String>,
+}
+
+/// Pull request (GitHub, Gitea) or merge request (GitLab) the pipeline runs for
+pub(crate) struct PullRequest {
+    pub number: String,
+    pub source_branch: String,
+    pub target_branch: String,
 }
@@ -1,16 +1,40 @@
+use crate::forges::ForgeConfig;
 use crate::pipelines::Pipeline;
 use crate::{config, git_service};
+use regex::Regex;
+use std::error::Error;
 
 pub(crate) struct GitRepo;
 
+const BRANCH_PREFERENCE: &str = r"^(main|master)$,^develop$,^release/.*$,^hotfix/.*$";
+
 impl Pipeline for GitRepo {
     fn name(&self) -> String {
         "Git Repo".to_string()
     }
 
+    // --branch or SVCI_BRANCH, then the checked out branch, then a branch at or containing a detached HEAD
     fn branch_name(&self) -> String {
-        git_service::branch_name(&config::clone_target_path())
-            .unwrap_or_else(|e| panic!("Failed to retrieve branch_name: {}", e))
+        if let Some(branch_name) = config::first_env_var(&["SVCI_BRANCH"]) {
+            eprintln!("branch {} (from --branch or SVCI_BRANCH)", branch_name);
+            return branch_name;
+        }
+
+        let repo_path = config::clone_target_path();
+        if let Ok(branch_name) = git_service::branch_name(&repo_path) {
+            return branch_name;
+        }
+        let (branch_name, strategy) =
+            git_service::detached_head_branch_name(&repo_path, &branch_preference())
+                .unwrap_or_else(|e| panic!("Failed to retrieve branch_name: {}", e));
+        eprintln!("branch {} (detached HEAD: {})", branch_name, strategy);
+
+        branch_name
+    }
+
+    fn commit_sha(&self) -> String {
+        git_service::commit_sha(&config::clone_target_path())
+            .unwrap_or_else(|e| panic!("Failed to retrieve commit_sha: {}", e))
     }
 
     fn short_commit_sha(&self) -> String {
@@ -29,7 +53,7 @@ impl Pipeline for GitRepo {
     }
 
     fn git_token(&self) -> String {
-        config::env_var("GIT_TOKEN")
+        config::env_var_or("GIT_TOKEN", "")
     }
 
     fn force_fetch_tags(&self) -> bool {
@@ -37,4 +61,22 @@ impl Pipeline for GitRepo {
         flag.parse()
             .unwrap_or_else(|e| panic!("{}\nFORCE_FETCH_TAGS: {}", e, flag))
     }
+
+    fn forge_config(&self) -> Result<Option<ForgeConfig>, Box<dyn Error>> {
+        let remote_url =
+            git_service::get_config_value(&config::clone_target_path(), "remote.origin.url");
+        ForgeConfig::detect(remote_url)
+    }
+}
+
+/// Comma separated patterns, most preferred first, for choosing between branches of a detached HEAD
+fn branch_preference() -> Vec<Regex> {
+    config::env_var_or("BRANCH_PREFERENCE", BRANCH_PREFERENCE)
+        .split(',')
+        .map(str::trim)
+        .filter(|pattern| !pattern.is_empty())
+        .map(|pattern| {
+            Regex::new(pattern).unwrap_or_else(|e| panic!("{}\nBRANCH_PREFERENCE: {}", e, pattern))
+        })
+        .collect()
 }
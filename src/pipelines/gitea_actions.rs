@@ -0,0 +1,84 @@
+use crate::forges::ForgeConfig;
+use crate::pipelines::github_actions::GithubActions;
+use crate::pipelines::{Pipeline, PullRequest};
+use crate::{config, git_service, http_service};
+use git2::Repository;
+use log::info;
+use std::env;
+use std::error::Error;
+
+pub(crate) struct GiteaActions;
+
+pub const GITEA_ACTIONS: &str = "GITEA_ACTIONS";
+const GITHUB_SERVER: &str = "https://github.com";
+
+// Gitea (and Forgejo) Actions exports the same GITHUB_* variables as GitHub Actions,
+// so everything except the forge is delegated to GithubActions.
+impl Pipeline for GiteaActions {
+    fn init(&self) {
+        git_service::set_global_config_value("safe.directory", &config::clone_target_path())
+            .unwrap();
+
+        if Repository::open(config::clone_target_path()).is_err() {
+            GithubActions.clone(&ForgeConfig::gitea());
+        }
+    }
+
+    fn name(&self) -> String {
+        "Gitea Actions".to_string()
+    }
+
+    fn branch_name(&self) -> String {
+        GithubActions.branch_name()
+    }
+
+    fn commit_sha(&self) -> String {
+        GithubActions.commit_sha()
+    }
+
+    fn short_commit_sha(&self) -> String {
+        GithubActions.short_commit_sha()
+    }
+
+    fn git_username(&self) -> String {
+        GithubActions.git_username()
+    }
+
+    fn git_email(&self) -> String {
+        "gitea-actions@noreply.gitea.com".to_string()
+    }
+
+    fn git_token(&self) -> String {
+        ForgeConfig::gitea().token()
+    }
+
+    fn forge_config(&self) -> Result<Option<ForgeConfig>, Box<dyn Error>> {
+        Ok(Some(ForgeConfig::gitea()))
+    }
+
+    fn pull_request(&self) -> Option<PullRequest> {
+        GithubActions.pull_request()
+    }
+
+    fn tag_name(&self) -> Option<String> {
+        GithubActions.tag_name()
+    }
+}
+
+/// Gitea runners may only export GITHUB_ACTIONS=true, so a non-GitHub server
+/// is probed for the Gitea version endpoint.
+pub(crate) fn is_gitea_server() -> bool {
+    let server_url = match env::var("GITHUB_SERVER_URL") {
+        Ok(url) => url.trim_end_matches('/').to_string(),
+        Err(_) => return false,
+    };
+    if server_url.is_empty() || server_url == GITHUB_SERVER {
+        return false;
+    }
+
+    info!("Probing {} for Gitea API", server_url);
+    match http_service::get(format!("{}/api/v1/version", server_url), None, None) {
+        Ok(parsed) => parsed.contains_key("version"),
+        Err(_) => false,
+    }
+}
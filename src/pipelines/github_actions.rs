@@ -1,11 +1,11 @@
-use crate::pipelines::Pipeline;
-use crate::release::Release;
-use crate::{config, git_service, http_service};
+use crate::forges::ForgeConfig;
+use crate::pipelines::{Pipeline, PullRequest};
+use crate::{config, git_service};
 use git2::Repository;
-use reqwest::header::HeaderMap;
-use serde_json::{json, Value};
-use std::collections::HashMap;
+use regex::Regex;
+use serde_json::Value;
 use std::error::Error;
+use std::fs;
 
 pub(crate) struct GithubActions;
 
@@ -18,7 +18,7 @@ impl Pipeline for GithubActions {
 
         // Clone
         if Repository::open(config::clone_target_path()).is_err() {
-            self.clone();
+            self.clone(&ForgeConfig::github());
         }
     }
 
@@ -26,12 +26,20 @@ impl Pipeline for GithubActions {
         "Github Actions".to_string()
     }
 
+    // GITHUB_REF_NAME is "<number>/merge" on pull_request events
     fn branch_name(&self) -> String {
-        config::env_var("GITHUB_REF_NAME")
+        match self.pull_request() {
+            Some(pull_request) => pull_request.source_branch,
+            None => config::env_var("GITHUB_REF_NAME"),
+        }
+    }
+
+    fn commit_sha(&self) -> String {
+        config::env_var("GITHUB_SHA")
     }
 
     fn short_commit_sha(&self) -> String {
-        let commit_sha = config::env_var("GITHUB_SHA");
+        let commit_sha = self.commit_sha();
         commit_sha[0..8].to_owned()
     }
 
@@ -44,56 +52,68 @@ impl Pipeline for GithubActions {
     }
 
     fn git_token(&self) -> String {
-        config::env_var("GITHUB_TOKEN")
+        ForgeConfig::github().token()
+    }
+
+    fn forge_config(&self) -> Result<Option<ForgeConfig>, Box<dyn Error>> {
+        Ok(Some(ForgeConfig::github()))
+    }
+
+    fn pull_request(&self) -> Option<PullRequest> {
+        let source_branch = config::first_env_var(&["GITHUB_HEAD_REF"])?;
+        let github_ref = config::env_var_or("GITHUB_REF", "");
+        // pull_request_target events run on the base branch
+        let number = Regex::new(r"^refs/pull/([0-9]+)/")
+            .unwrap()
+            .captures(&github_ref)
+            .map(|captures| captures[1].to_string())
+            .or_else(event_pull_request_number);
+        let Some(number) = number else {
+            log::warn!(
+                "No pull request number in GITHUB_REF {} or GITHUB_EVENT_PATH. Versioning as a branch",
+                github_ref
+            );
+            return None;
+        };
+
+        Some(PullRequest {
+            number,
+            source_branch,
+            target_branch: config::env_var("GITHUB_BASE_REF"),
+        })
     }
 
-    fn create_release(&self, release: &Release) -> Result<HashMap<String, Value>, Box<dyn Error>> {
-        let url = format!(
-            "{}/repos/{}/releases",
-            config::env_var("GITHUB_API_URL"),
-            config::env_var("GITHUB_REPOSITORY")
-        );
-
-        let mut headers = HeaderMap::new();
-        headers.insert("User-Agent", "Semver-CI".parse().unwrap());
-        headers.insert("Accept", "application/vnd.github+json".parse().unwrap());
-        headers.insert(
-            "Authorization",
-            format!("Bearer {}", self.git_token()).parse().unwrap(),
-        );
-
-        let mut body = HashMap::new();
-        body.insert("name", json!(release.name.clone()));
-        body.insert("body", json!(release.description.clone()));
-        body.insert("tag_name", json!(release.tag_name.clone()));
-        body.insert("target_commitish", json!(config::env_var("GITHUB_SHA")));
-        body.insert(
-            "generate_release_notes",
-            json!(release.generate_release_notes),
-        );
-
-        http_service::post(url, Some(headers), Some(body))
+    fn tag_name(&self) -> Option<String> {
+        match config::env_var_or("GITHUB_REF_TYPE", "").as_str() {
+            "tag" => Some(config::env_var("GITHUB_REF_NAME")),
+            _ => None,
+        }
     }
 }
 
+/// `.pull_request.number` of the event payload
+fn event_pull_request_number() -> Option<String> {
+    let event = fs::read_to_string(config::first_env_var(&["GITHUB_EVENT_PATH"])?).ok()?;
+    let event: Value = serde_json::from_str(&event).ok()?;
+    event["pull_request"]["number"]
+        .as_u64()
+        .map(|number| number.to_string())
+}
+
 impl GithubActions {
     fn add_safe_directory(&self) {
         git_service::set_global_config_value("safe.directory", &config::clone_target_path())
             .unwrap();
     }
 
-    fn clone(&self) {
+    pub(crate) fn clone(&self, forge_config: &ForgeConfig) {
         // Clone repo
-        let repo_url = format!(
-            "{}/{}.git",
-            config::env_var("GITHUB_SERVER_URL"),
-            config::env_var("GITHUB_REPOSITORY")
-        );
+        let repo_url = format!("{}.git", forge_config.project_url);
         let repo = git_service::clone(
             &repo_url,
             &config::clone_target_path(),
             &self.git_username(),
-            &self.git_token(),
+            &forge_config.token(),
             20,
         )
         .unwrap_or_else(|e| panic!("{}", e));
@@ -101,8 +121,14 @@ impl GithubActions {
         // Fetch GITHUB_REF
         let github_ref = config::env_var("GITHUB_REF");
         let refspec = format!("{}:{}", github_ref, github_ref);
-        git_service::fetch_refs(&repo, &self.git_username(), &self.git_token(), &[&refspec])
-            .unwrap_or_else(|e| panic!("Failed to fetch GITHUB_REF: {}\n{}", github_ref, e));
+        git_service::fetch_refs(
+            &repo,
+            "origin",
+            &self.git_username(),
+            &forge_config.token(),
+            &[&refspec],
+        )
+        .unwrap_or_else(|e| panic!("Failed to fetch GITHUB_REF: {}\n{}", github_ref, e));
 
         // Checkout GITHUB_REF
         git_service::checkout(&repo, &github_ref).unwrap_or_else(|e| panic!("{}", e));
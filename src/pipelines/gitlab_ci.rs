@@ -1,20 +1,15 @@
-use crate::pipelines::Pipeline;
-use crate::release::Release;
-use crate::{config, git_service, http_service};
-use log::error;
-use reqwest::header::HeaderMap;
-use serde_json::{json, Value};
-use std::collections::HashMap;
+use crate::forges::ForgeConfig;
+use crate::pipelines::{Pipeline, PullRequest};
+use crate::{config, git_service};
 use std::error::Error;
 
 pub(crate) struct GitlabCI;
 
 pub const GITLAB_CI: &str = "GITLAB_CI";
-const IGNORE_CHANGE_PREFIXES: [&str; 5] = ["refactor:", "style:", "test:", "chore:", "Merge "];
 
 impl Pipeline for GitlabCI {
     fn init(&self) {
-        self.git_origin_pushurl(config::env_var("CI_PROJECT_URL"));
+        self.git_origin_pushurl(ForgeConfig::gitlab().project_url);
     }
 
     fn name(&self) -> String {
@@ -22,7 +17,14 @@ impl Pipeline for GitlabCI {
     }
 
     fn branch_name(&self) -> String {
-        config::env_var("CI_COMMIT_REF_NAME")
+        match self.pull_request() {
+            Some(merge_request) => merge_request.source_branch,
+            None => config::env_var("CI_COMMIT_REF_NAME"),
+        }
+    }
+
+    fn commit_sha(&self) -> String {
+        config::env_var("CI_COMMIT_SHA")
     }
 
     fn short_commit_sha(&self) -> String {
@@ -41,148 +43,29 @@ impl Pipeline for GitlabCI {
         config::env_var_or("SEMVER_CI_TOKEN", &config::env_var("CI_JOB_TOKEN"))
     }
 
-    fn create_release(&self, release: &Release) -> Result<HashMap<String, Value>, Box<dyn Error>> {
-        let url = format!(
-            "{}/projects/{}/releases",
-            config::env_var("CI_API_V4_URL"),
-            config::env_var("CI_PROJECT_ID")
-        );
-
-        let mut headers = HeaderMap::new();
-        headers.insert(
-            "JOB-TOKEN",
-            config::env_var("CI_JOB_TOKEN").parse().unwrap(),
-        );
-
-        let description = self.release_notes(
-            release.description.clone(),
-            release.generate_release_notes,
-            &release.previous_tag,
-            &config::env_var("CI_COMMIT_SHA"),
-        );
+    fn forge_config(&self) -> Result<Option<ForgeConfig>, Box<dyn Error>> {
+        Ok(Some(ForgeConfig::gitlab()))
+    }
 
-        let mut body = HashMap::new();
-        body.insert("name", json!(release.name.clone()));
-        body.insert("description", json!(description));
-        body.insert("tag_name", json!(release.tag_name.clone()));
-        body.insert("tag_message", json!(release.tag_message.clone()));
-        body.insert("ref", json!(config::env_var("CI_COMMIT_SHA")));
+    fn pull_request(&self) -> Option<PullRequest> {
+        Some(PullRequest {
+            number: config::first_env_var(&["CI_MERGE_REQUEST_IID"])?,
+            source_branch: config::env_var("CI_MERGE_REQUEST_SOURCE_BRANCH_NAME"),
+            target_branch: config::env_var("CI_MERGE_REQUEST_TARGET_BRANCH_NAME"),
+        })
+    }
 
-        http_service::post(url, Some(headers), Some(body))
+    fn tag_name(&self) -> Option<String> {
+        config::first_env_var(&["CI_COMMIT_TAG"])
     }
 }
 
 impl GitlabCI {
+    // Only origin is the CI checkout remote whose URL carries the read-only job token
     fn git_origin_pushurl(&self, url: String) {
         let name = "remote.origin.pushurl";
         let value = format!("{}.git", url);
         git_service::set_config_value(&config::clone_target_path(), name, &value)
             .unwrap_or_else(|e| panic!("{}", e));
     }
-
-    fn release_notes(&self, prepend: String, auto_generate: bool, from: &str, to: &str) -> String {
-        let mut notes = prepend.clone();
-
-        if auto_generate {
-            if from == "v0.0.0" {
-                notes += &self.commits(to);
-            } else {
-                notes += &self.compare(from, to).unwrap_or_else(|e| {
-                    error!("{}", e);
-                    self.web_compare_url(from, to)
-                })
-            }
-        }
-
-        notes
-    }
-
-    fn commits(&self, ref_name: &str) -> String {
-        let commits = format!(
-            "{}/-/commits/{}",
-            config::env_var("CI_PROJECT_URL"),
-            ref_name
-        );
-
-        format!(r#"Full Changelog: {}"#, commits)
-    }
-
-    fn web_compare_url(&self, from: &str, to: &str) -> String {
-        format!(
-            r#"# What's Changed
-{}/-/compare/{}...{}"#,
-            config::env_var("CI_PROJECT_URL"),
-            from,
-            to
-        )
-    }
-
-    fn compare(&self, from: &str, to: &str) -> Result<String, Box<dyn Error>> {
-        let url = format!(
-            "{}/projects/{}/repository/compare",
-            config::env_var("CI_API_V4_URL"),
-            config::env_var("CI_PROJECT_ID")
-        );
-
-        let mut headers = HeaderMap::new();
-        headers.insert(
-            "JOB-TOKEN",
-            config::env_var("CI_JOB_TOKEN").parse().unwrap(),
-        );
-
-        let mut query = HashMap::new();
-        query.insert("from", from);
-        query.insert("to", to);
-
-        let parsed = http_service::get(url, Some(headers), Some(query))?;
-        let commits = self.collect_commits(&parsed);
-        let empty_string_value = Value::String("".to_string());
-        let full_diff = parsed
-            .get("web_url")
-            .unwrap_or(&empty_string_value)
-            .as_str()
-            .unwrap_or("");
-
-        Ok(format!(
-            r#"## What's Changed
-{}
-
-Full Changelog: {}"#,
-            commits, full_diff
-        ))
-    }
-
-    fn collect_commits(&self, compare_response: &HashMap<String, Value>) -> String {
-        let empty_string_value = Value::String("".to_string());
-
-        compare_response
-            .get("commits")
-            .unwrap_or(&Value::Array(vec![]))
-            .as_array()
-            .unwrap_or(&Vec::new())
-            .iter()
-            .filter_map(|object| {
-                let message = object
-                    .get("message")
-                    .unwrap_or(&empty_string_value)
-                    .as_str()
-                    .unwrap_or("");
-                let web_url = object
-                    .get("web_url")
-                    .unwrap_or(&empty_string_value)
-                    .as_str()
-                    .unwrap_or("");
-                if (message.is_empty() && web_url.is_empty())
-                    || IGNORE_CHANGE_PREFIXES
-                        .iter()
-                        .any(|&prefix| message.starts_with(prefix))
-                {
-                    None
-                } else {
-                    Some(format!("* [{}]({})", message, web_url))
-                }
-            })
-            .collect::<Vec<String>>()
-            .join("\n")
-    }
 }
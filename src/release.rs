@@ -5,4 +5,5 @@ pub(crate) struct Release {
     pub(crate) tag_message: String,
     pub(crate) generate_release_notes: bool,
     pub(crate) previous_tag: String,
+    pub(crate) attachments: Vec<String>,
 }
@@ -1,5 +1,8 @@
 use std::cmp::Ordering;
 
+/// Pre-release stage of pull/merge request versions, followed by the request number
+pub const PULL_REQUEST_STAGE: &str = "pr";
+
 #[derive(Eq, PartialEq, Debug)]
 pub struct SemanticVersion {
     pub major: u64,
@@ -132,22 +135,13 @@ impl SemanticVersion {
     }
 
     pub fn to_string(&self, prefix_v: bool) -> String {
-        let version_string = match self.prerelease_stage.as_str() {
-            "dev" => format!(
-                "{}.{}.{}-{}.{}.{}",
-                self.major,
-                self.minor,
-                self.patch,
-                self.prerelease_stage,
-                self.prerelease_number,
-                self.commit_short_sha
-            ),
-            "rc" => format!(
-                "{}.{}.{}-{}.{}",
-                self.major, self.minor, self.patch, self.prerelease_stage, self.prerelease_number
-            ),
-            _ => format!("{}.{}.{}", self.major, self.minor, self.patch),
-        };
+        let version_string = format!(
+            "{}.{}.{}{}",
+            self.major,
+            self.minor,
+            self.patch,
+            self.prerelease_suffix()
+        );
 
         if prefix_v {
             format!("v{}", version_string)
@@ -156,6 +150,22 @@ impl SemanticVersion {
         }
     }
 
+    /// `-<stage>.<number>[.<sha>]` part of the version, empty for official versions.
+    /// rc versions are written without the commit SHA
+    pub fn prerelease_suffix(&self) -> String {
+        match self.prerelease_stage.as_str() {
+            "" => "".to_string(),
+            "rc" => format!("-{}.{}", self.prerelease_stage, self.prerelease_number),
+            _ => match self.commit_short_sha.is_empty() {
+                true => format!("-{}.{}", self.prerelease_stage, self.prerelease_number),
+                false => format!(
+                    "-{}.{}.{}",
+                    self.prerelease_stage, self.prerelease_number, self.commit_short_sha
+                ),
+            },
+        }
+    }
+
     pub fn default() -> Self {
         SemanticVersion {
             major: 0,
@@ -168,18 +178,233 @@ impl SemanticVersion {
     }
 }
 
+/// Version requirement: comparator sets separated by `||`, any of which must match. A set is
+/// comparators separated by spaces or commas, all of which must match, e.g. `^1.2`, `~1.4.0`,
+/// `>=1.2.0, <2.0.0`, `1.2 - 1.4` or `1.x || >=2.1`.
+///
+/// As in npm and cargo, a pre-release only matches a set with a comparator on the same
+/// major.minor.patch that has a pre-release, so `^1.2` does not match `1.3.0-rc.1`.
+#[derive(Debug)]
+pub struct VersionReq {
+    sets: Vec<Vec<Comparator>>,
+}
+
+#[derive(Debug, PartialEq)]
+enum Op {
+    Exact,
+    Greater,
+    GreaterEq,
+    Less,
+    LessEq,
+    Tilde,
+    Caret,
+}
+
+const OPERATORS: [&str; 7] = ["=", ">", ">=", "<", "<=", "~", "^"];
+
+// Missing or wildcard (x, X, *) parts are None
+#[derive(Debug)]
+struct Comparator {
+    op: Op,
+    major: Option<u64>,
+    minor: Option<u64>,
+    patch: Option<u64>,
+    prerelease: Option<(String, u64)>,
+}
+
+impl VersionReq {
+    pub fn parse(req: &str) -> Result<Self, String> {
+        let sets = req
+            .split("||")
+            .map(|set| parse_comparator_set(set, req))
+            .collect::<Result<Vec<Vec<Comparator>>, String>>()?;
+        Ok(VersionReq { sets })
+    }
+
+    pub fn matches(&self, version: &SemanticVersion) -> bool {
+        self.sets.iter().any(|set| {
+            set.iter().all(|comparator| comparator.matches(version))
+                && (version.prerelease_stage.is_empty()
+                    || set
+                        .iter()
+                        .any(|comparator| comparator.allows_prerelease_of(version)))
+        })
+    }
+}
+
+fn parse_comparator_set(set: &str, req: &str) -> Result<Vec<Comparator>, String> {
+    let invalid = || format!("Invalid version requirement: {}", req);
+
+    let mut tokens = vec![];
+    let mut pending_op: Option<String> = None;
+    for token in set.split([' ', ',']).filter(|token| !token.is_empty()) {
+        // ">= 1.2" is written with a space
+        let token = format!("{}{}", pending_op.take().unwrap_or_default(), token);
+        if OPERATORS.contains(&token.as_str()) {
+            pending_op = Some(token);
+            continue;
+        }
+        tokens.push(token);
+    }
+    if pending_op.is_some() {
+        return Err(invalid());
+    }
+
+    let mut comparators = vec![];
+    let mut i = 0;
+    while i < tokens.len() {
+        // Hyphen range: "1.2.3 - 1.4" is ">=1.2.3 <=1.4"
+        if tokens.get(i + 1).map(String::as_str) == Some("-") {
+            let last = tokens.get(i + 2).ok_or_else(invalid)?;
+            comparators.push(Comparator::parse(&format!(">={}", tokens[i]))?);
+            comparators.push(Comparator::parse(&format!("<={}", last))?);
+            i += 3;
+        } else {
+            comparators.push(Comparator::parse(&tokens[i])?);
+            i += 1;
+        }
+    }
+    if comparators.is_empty() {
+        return Err(invalid());
+    }
+    Ok(comparators)
+}
+
+impl Comparator {
+    fn parse(token: &str) -> Result<Self, String> {
+        let (op, version) = [
+            (">=", Op::GreaterEq),
+            ("<=", Op::LessEq),
+            (">", Op::Greater),
+            ("<", Op::Less),
+            ("=", Op::Exact),
+            ("~", Op::Tilde),
+            ("^", Op::Caret),
+        ]
+        .into_iter()
+        .find_map(|(prefix, op)| token.strip_prefix(prefix).map(|version| (op, version)))
+        .unwrap_or((Op::Exact, token));
+        let version = version.strip_prefix('v').unwrap_or(version);
+
+        let invalid = || format!("Invalid version requirement: {}", token);
+        let (numbers, prerelease) = match version.split_once('-') {
+            Some((numbers, metadata_string)) => {
+                let (stage, number, _) = metadata(metadata_string)?;
+                (numbers, Some((stage, number)))
+            }
+            None => (version, None),
+        };
+        let parts = numbers
+            .split('.')
+            .map(|part| match part {
+                "x" | "X" | "*" => Ok(None),
+                _ => part.parse::<u64>().map(Some).map_err(|_| invalid()),
+            })
+            .collect::<Result<Vec<Option<u64>>, String>>()?;
+        let wildcard_followed_by_number = parts
+            .windows(2)
+            .any(|pair| pair[0].is_none() && pair[1].is_some());
+        if parts.len() > 3
+            || wildcard_followed_by_number
+            || (prerelease.is_some() && parts.iter().filter(|part| part.is_some()).count() < 3)
+        {
+            return Err(invalid());
+        }
+
+        Ok(Comparator {
+            op,
+            major: parts[0],
+            minor: parts.get(1).copied().flatten(),
+            patch: parts.get(2).copied().flatten(),
+            prerelease,
+        })
+    }
+
+    fn matches(&self, version: &SemanticVersion) -> bool {
+        // "*" matches any version, so nothing is greater or less than it
+        if self.major.is_none() {
+            return !matches!(self.op, Op::Greater | Op::Less);
+        }
+
+        let lower = self.lower();
+        let upper = self.upper();
+        match self.op {
+            Op::Greater if self.patch.is_some() => version > &lower,
+            Op::Less => version < &lower,
+            Op::LessEq if self.patch.is_some() => version <= &lower,
+            Op::Greater => version >= &upper,
+            Op::LessEq => version < &upper,
+            Op::GreaterEq => version >= &lower,
+            Op::Exact if self.patch.is_some() => version.cmp(&lower) == Ordering::Equal,
+            Op::Exact | Op::Tilde | Op::Caret => version >= &lower && version < &upper,
+        }
+    }
+
+    fn allows_prerelease_of(&self, version: &SemanticVersion) -> bool {
+        self.prerelease.is_some()
+            && (self.major, self.minor, self.patch)
+                == (
+                    Some(version.major),
+                    Some(version.minor),
+                    Some(version.patch),
+                )
+    }
+
+    // Version written in the comparator, missing parts as 0
+    fn lower(&self) -> SemanticVersion {
+        let (prerelease_stage, prerelease_number) = self.prerelease.clone().unwrap_or_default();
+        SemanticVersion {
+            major: self.major.unwrap_or(0),
+            minor: self.minor.unwrap_or(0),
+            patch: self.patch.unwrap_or(0),
+            prerelease_stage,
+            prerelease_number,
+            commit_short_sha: "".to_string(),
+        }
+    }
+
+    // First version beyond the comparator's range, ignoring pre-releases
+    fn upper(&self) -> SemanticVersion {
+        let major = self.major.unwrap_or(0);
+        let mut upper = SemanticVersion::default();
+        match (&self.op, self.minor, self.patch) {
+            (Op::Caret, Some(0), Some(patch)) if major == 0 => {
+                upper.minor = 0;
+                upper.patch = patch + 1;
+            }
+            (Op::Caret, Some(minor), _) if major == 0 => upper.minor = minor + 1,
+            (Op::Caret, _, _) | (_, None, _) => upper.major = major + 1,
+            (Op::Tilde, Some(minor), _) | (_, Some(minor), None) => {
+                upper.major = major;
+                upper.minor = minor + 1;
+            }
+            (_, Some(minor), Some(patch)) => {
+                upper.major = major;
+                upper.minor = minor;
+                upper.patch = patch + 1;
+            }
+        }
+        upper
+    }
+}
+
 fn version_part(part: &str, scope: &str) -> Result<u64, String> {
     part.parse::<u64>()
         .map_err(|e| format!("Invalid {} version: {}\n{}", scope, part, e))
 }
 
 fn metadata(metadata_string: &str) -> Result<(String, u64, String), String> {
-    let metadata_parts: Vec<&str> = metadata_string.split('.').collect();
+    let mut metadata_parts: Vec<&str> = metadata_string.split('.').collect();
     if metadata_parts.len() < 2 {
         return Err(format!("Invalid metadata format: {}", metadata_string));
     }
 
-    let prerelease_stage = metadata_parts[0].to_string();
+    // pr.<number>.<prerelease number>.<sha>
+    let prerelease_stage = if metadata_parts[0] == PULL_REQUEST_STAGE && metadata_parts.len() > 2 {
+        format!("{}.{}", PULL_REQUEST_STAGE, metadata_parts.remove(1))
+    } else {
+        metadata_parts[0].to_string()
+    };
     let prerelease_number = metadata_parts[1].parse::<u64>().map_err(|_| {
         format!(
             "Invalid prerelease number: {}, Metadata: {}",
@@ -215,6 +440,76 @@ mod tests {
         assert_eq!(dev.prerelease_number, 7);
         assert_eq!(dev.commit_short_sha, "abcd1234");
         assert_eq!(dev.to_string(false), "1.2.3-dev.7.abcd1234");
+
+        let pr = SemanticVersion::from_string("v1.3.0-pr.123.2.abcd1234".to_string()).unwrap();
+        assert_eq!(pr.prerelease_stage, "pr.123");
+        assert_eq!(pr.prerelease_number, 2);
+        assert_eq!(pr.commit_short_sha, "abcd1234");
+        assert_eq!(pr.to_string(true), "v1.3.0-pr.123.2.abcd1234");
+    }
+
+    fn satisfies(req: &str, version: &str) -> bool {
+        VersionReq::parse(req)
+            .unwrap()
+            .matches(&SemanticVersion::from_string(version.to_string()).unwrap())
+    }
+
+    #[test]
+    fn version_requirements() {
+        assert!(satisfies("^1.2", "1.2.0"));
+        assert!(satisfies("^1.2", "v1.9.3"));
+        assert!(!satisfies("^1.2", "2.0.0"));
+        assert!(!satisfies("^1.2", "1.1.9"));
+        assert!(satisfies("^0.2.3", "0.2.9"));
+        assert!(!satisfies("^0.2.3", "0.3.0"));
+        assert!(!satisfies("^0.0.3", "0.0.4"));
+
+        assert!(satisfies("~1.4", "1.4.7"));
+        assert!(!satisfies("~1.4.2", "1.5.0"));
+        assert!(!satisfies("~1.4.2", "1.4.1"));
+
+        assert!(satisfies(">=1.2.0, <2.0.0", "1.5.0"));
+        assert!(satisfies(">= 1.2 < 2", "1.2.0"));
+        assert!(!satisfies(">=1.2.0 <2.0.0", "2.0.0"));
+        assert!(satisfies(">1.2", "1.3.0"));
+        assert!(!satisfies(">1.2", "1.2.5"));
+        assert!(satisfies("<=1.2", "1.2.5"));
+        assert!(satisfies("=1.2.3", "v1.2.3"));
+        assert!(satisfies("1.2", "1.2.3"));
+        assert!(satisfies(">=1.3.0-rc.1", "1.3.0-rc.2"));
+
+        assert!(VersionReq::parse("^1.x.y").is_err());
+        assert!(VersionReq::parse("1.x.3").is_err());
+        assert!(VersionReq::parse(">=").is_err());
+        assert!(VersionReq::parse("^1 ||").is_err());
+    }
+
+    #[test]
+    fn version_requirement_ranges() {
+        assert!(satisfies("1.x || >=2.1", "1.7.0"));
+        assert!(satisfies("1.x || >=2.1", "2.1.0"));
+        assert!(!satisfies("1.x || >=2.1", "2.0.5"));
+
+        assert!(satisfies("1.2.3 - 1.4", "1.4.9"));
+        assert!(!satisfies("1.2.3 - 1.4", "1.5.0"));
+        assert!(!satisfies("1.2.3 - 1.4.0", "1.4.1"));
+        assert!(!satisfies("1.2.3 - 1.4", "1.2.2"));
+
+        assert!(satisfies("*", "3.1.4"));
+        assert!(satisfies("1.2.*", "1.2.8"));
+        assert!(!satisfies("1.X", "2.0.0"));
+        assert!(satisfies("^1.x", "1.9.0"));
+    }
+
+    #[test]
+    fn version_requirement_prereleases() {
+        assert!(!satisfies("^1.2", "1.3.0-rc.1"));
+        assert!(!satisfies("*", "1.3.0-rc.1"));
+        assert!(satisfies(">=1.3.0-rc.1", "1.3.0-rc.2"));
+        assert!(!satisfies(">=1.3.0-rc.1", "1.4.0-rc.1"));
+        assert!(satisfies(">=1.3.0-rc.1", "1.4.0"));
+        assert!(satisfies("^1.3.0-rc.1 || ^2", "1.3.0-rc.3"));
+        assert!(satisfies("=1.3.0-dev.2", "1.3.0-dev.2.abcd1234"));
     }
 
     #[test]
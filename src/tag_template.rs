@@ -0,0 +1,198 @@
+use crate::semantic_version::SemanticVersion;
+use crate::version_scheme::VersionScheme;
+use regex::Regex;
+
+pub(crate) const DEFAULT_TAG_TEMPLATE: &str = "{prefix}{major}.{minor}.{patch}{pre}{build}";
+
+/// How version tags are named, e.g. `{prefix}{major}.{minor}.{patch}{pre}{build}`,
+/// `release-{major}.{minor}.{patch}{pre}{build}` or `app@{major}.{minor}.{patch}{pre}{build}`.
+/// `{pre}` is `-<stage>.<number>` and `{build}` the `.<sha>` of dev and pull request versions.
+/// `{prefix}` is the tag prefix. Discovered tags may omit it or start with `v` instead
+pub(crate) struct TagTemplate {
+    template: String,
+    prefix: String,
+    regex: Regex,
+}
+
+impl TagTemplate {
+    pub(crate) fn new(template: &str, prefix: &str) -> Result<Self, String> {
+        let placeholder_regex = Regex::new(r"\{([^{}]*)\}").unwrap();
+        let mut pattern = "^".to_string();
+        let mut placeholders = vec![];
+        let mut literal_start = 0;
+        for captures in placeholder_regex.captures_iter(template) {
+            let placeholder = captures.get(0).unwrap();
+            let name = captures.get(1).unwrap().as_str();
+            pattern += &regex::escape(&template[literal_start..placeholder.start()]);
+            literal_start = placeholder.end();
+
+            pattern += &match name {
+                "prefix" => format!("(?P<prefix>(?:{}|v)?)", regex::escape(prefix)),
+                "major" | "minor" | "patch" => format!("(?P<{}>[0-9]+)", name),
+                "pre" => r"(?P<pre>-[0-9A-Za-z]+(?:\.[0-9A-Za-z]+)*)?".to_string(),
+                "build" => r"(?P<build>\.[0-9A-Za-z]+)?".to_string(),
+                _ => {
+                    return Err(format!(
+                        "Invalid tag template: {}. Unknown placeholder {{{}}}",
+                        template, name
+                    ))
+                }
+            };
+            if placeholders.contains(&name) {
+                return Err(format!(
+                    "Invalid tag template: {}. Duplicate placeholder {{{}}}",
+                    template, name
+                ));
+            }
+            placeholders.push(name);
+        }
+        pattern += &regex::escape(&template[literal_start..]);
+        pattern += "$";
+
+        for required in ["major", "minor", "patch", "pre"] {
+            if !placeholders.contains(&required) {
+                return Err(format!(
+                    "Invalid tag template: {}. Missing placeholder {{{}}}",
+                    template, required
+                ));
+            }
+        }
+
+        Ok(TagTemplate {
+            template: template.to_string(),
+            prefix: prefix.to_string(),
+            regex: Regex::new(&pattern).map_err(|e| e.to_string())?,
+        })
+    }
+
+    pub(crate) fn as_str(&self) -> &str {
+        &self.template
+    }
+
+    pub(crate) fn uses_prefix(&self) -> bool {
+        self.template.contains("{prefix}")
+    }
+
+    /// Version of a tag named after the template
+    pub(crate) fn parse(&self, tag_name: &str) -> Option<SemanticVersion> {
+        let [_, major, minor, patch, pre, build] = self.parts(tag_name)?;
+        SemanticVersion::from_string(format!("{}.{}.{}{}{}", major, minor, patch, pre, build)).ok()
+    }
+
+    pub(crate) fn render(&self, version: &SemanticVersion, scheme: &dyn VersionScheme) -> String {
+        let core = scheme.core(version);
+        let mut parts = core.split('.');
+        let suffix = version.prerelease_suffix();
+        let pre = match version.prerelease_stage.is_empty() {
+            true => "".to_string(),
+            false => format!(
+                "-{}.{}",
+                version.prerelease_stage, version.prerelease_number
+            ),
+        };
+
+        self.render_parts([
+            &self.prefix,
+            parts.next().unwrap_or_default(),
+            parts.next().unwrap_or_default(),
+            parts.next().unwrap_or_default(),
+            &pre,
+            &suffix[pre.len()..],
+        ])
+    }
+
+    /// A plain version such as `v1.2.3-rc.1` named after the template, keeping its digits as is
+    pub(crate) fn render_version_string(&self, version_string: &str) -> Option<String> {
+        let plain = TagTemplate::new(DEFAULT_TAG_TEMPLATE, "v").unwrap();
+        let [_, major, minor, patch, pre, build] = plain.parts(version_string)?;
+        Some(self.render_parts([&self.prefix, major, minor, patch, pre, build]))
+    }
+
+    /// The official version tag of a pre-release tag, e.g. v1.2.3 of v1.2.3-rc.1
+    pub(crate) fn official_tag_name(&self, tag_name: &str) -> Option<String> {
+        let [prefix, major, minor, patch, _, _] = self.parts(tag_name)?;
+        Some(self.render_parts([prefix, major, minor, patch, "", ""]))
+    }
+
+    fn parts<'a>(&self, tag_name: &'a str) -> Option<[&'a str; 6]> {
+        let captures = self.regex.captures(tag_name)?;
+        let part = |name: &str| captures.name(name).map_or("", |part| part.as_str());
+        Some([
+            part("prefix"),
+            part("major"),
+            part("minor"),
+            part("patch"),
+            part("pre"),
+            part("build"),
+        ])
+    }
+
+    fn render_parts(&self, [prefix, major, minor, patch, pre, build]: [&str; 6]) -> String {
+        self.template
+            .replace("{prefix}", prefix)
+            .replace("{major}", major)
+            .replace("{minor}", minor)
+            .replace("{patch}", patch)
+            .replace("{pre}", pre)
+            .replace("{build}", build)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::version_scheme::SemVer;
+
+    fn version(version: &str) -> SemanticVersion {
+        SemanticVersion::from_string(version.to_string()).unwrap()
+    }
+
+    #[test]
+    fn render_and_parse_templates() {
+        let default = TagTemplate::new(DEFAULT_TAG_TEMPLATE, "v").unwrap();
+        assert_eq!(default.render(&version("1.2.3"), &SemVer), "v1.2.3");
+        assert_eq!(
+            default.render(&version("1.2.3-dev.4.abcd1234"), &SemVer),
+            "v1.2.3-dev.4.abcd1234"
+        );
+        assert_eq!(default.parse("1.2.3-rc.1"), Some(version("1.2.3-rc.1")));
+        assert_eq!(
+            default.parse("v1.2.3-pr.12.1.abcd1234"),
+            Some(version("1.2.3-pr.12.1.abcd1234"))
+        );
+        assert_eq!(default.parse("v1.2"), None);
+
+        let release = TagTemplate::new("release-{major}.{minor}.{patch}{pre}{build}", "v").unwrap();
+        assert_eq!(release.render(&version("1.2.3"), &SemVer), "release-1.2.3");
+        assert_eq!(release.parse("release-1.2.3"), Some(version("1.2.3")));
+        assert_eq!(release.parse("v1.2.3"), None);
+
+        let final_suffix = TagTemplate::new("{major}.{minor}.{patch}{pre}-final", "").unwrap();
+        assert_eq!(
+            final_suffix.render(&version("1.2.3-dev.1.abcd1234"), &SemVer),
+            "1.2.3-dev.1-final"
+        );
+        assert_eq!(final_suffix.parse("1.2.3-final"), Some(version("1.2.3")));
+        assert_eq!(
+            final_suffix.parse("1.2.3-rc.2-final"),
+            Some(version("1.2.3-rc.2"))
+        );
+
+        assert_eq!(
+            release.render_version_string("v2024.05.1-rc.1"),
+            Some("release-2024.05.1-rc.1".to_string())
+        );
+        assert_eq!(release.render_version_string("latest"), None);
+        assert_eq!(
+            default.official_tag_name("1.2.3-rc.1"),
+            Some("1.2.3".to_string())
+        );
+        assert_eq!(
+            release.official_tag_name("release-1.2.3-rc.1"),
+            Some("release-1.2.3".to_string())
+        );
+
+        assert!(TagTemplate::new("{major}.{minor}", "").is_err());
+        assert!(TagTemplate::new("{major}.{minor}.{patch}{pre}{sha}", "").is_err());
+    }
+}
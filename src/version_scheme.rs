@@ -0,0 +1,180 @@
+use crate::semantic_version::SemanticVersion;
+use chrono::{DateTime, Datelike, NaiveDate};
+
+pub(crate) const SEMVER: &str = "semver";
+pub(crate) const CALVER: &str = "calver";
+
+/// How official versions are increased and written in tags. Versions of every scheme are held
+/// in `SemanticVersion`, so pre-releases, tag discovery and ordering work the same way
+pub(crate) trait VersionScheme {
+    /// Official version after `last` for a branch of `scope`
+    fn increase(&self, last: &SemanticVersion, scope: &str) -> SemanticVersion;
+
+    /// major.minor.patch part of `version` as written in tags
+    fn core(&self, version: &SemanticVersion) -> String;
+}
+
+pub(crate) struct SemVer;
+
+impl VersionScheme for SemVer {
+    fn increase(&self, last: &SemanticVersion, scope: &str) -> SemanticVersion {
+        last.clone().increase_by_scope(scope.to_string())
+    }
+
+    fn core(&self, version: &SemanticVersion) -> String {
+        format!("{}.{}.{}", version.major, version.minor, version.patch)
+    }
+}
+
+/// Calendar versioning: two date tokens and MICRO, e.g. `YYYY.0M.MICRO` or `YY.0W.MICRO`.
+/// MICRO restarts at 0 when the date part changes
+pub(crate) struct CalVer {
+    tokens: [DateToken; 2],
+    date: NaiveDate,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum DateToken {
+    FullYear,
+    ShortYear,
+    ZeroPaddedYear,
+    Month,
+    ZeroPaddedMonth,
+    Week,
+    ZeroPaddedWeek,
+    Day,
+    ZeroPaddedDay,
+}
+
+impl CalVer {
+    pub(crate) fn new(format: &str, date: NaiveDate) -> Result<Self, String> {
+        let invalid = || {
+            format!(
+                "Invalid CalVer format: {}. Expected two date tokens and MICRO, e.g. YYYY.0M.MICRO",
+                format
+            )
+        };
+        let token = |part: &str| match part {
+            "YYYY" => Ok(DateToken::FullYear),
+            "YY" => Ok(DateToken::ShortYear),
+            "0Y" => Ok(DateToken::ZeroPaddedYear),
+            "MM" => Ok(DateToken::Month),
+            "0M" => Ok(DateToken::ZeroPaddedMonth),
+            "WW" => Ok(DateToken::Week),
+            "0W" => Ok(DateToken::ZeroPaddedWeek),
+            "DD" => Ok(DateToken::Day),
+            "0D" => Ok(DateToken::ZeroPaddedDay),
+            _ => Err(invalid()),
+        };
+
+        match format.split('.').collect::<Vec<&str>>()[..] {
+            [first, second, "MICRO"] => Ok(CalVer {
+                tokens: [token(first)?, token(second)?],
+                date,
+            }),
+            _ => Err(invalid()),
+        }
+    }
+
+    fn date_parts(&self) -> (u64, u64) {
+        // Weeks belong to ISO week-numbering years, e.g. 2024-12-30 is in week 1 of 2025
+        let uses_week = self
+            .tokens
+            .iter()
+            .any(|token| matches!(token, DateToken::Week | DateToken::ZeroPaddedWeek));
+        let year = match uses_week {
+            true => self.date.iso_week().year(),
+            false => self.date.year(),
+        } as u64;
+
+        let value = |token: DateToken| match token {
+            DateToken::FullYear => year,
+            DateToken::ShortYear | DateToken::ZeroPaddedYear => year % 100,
+            DateToken::Month | DateToken::ZeroPaddedMonth => self.date.month() as u64,
+            DateToken::Week | DateToken::ZeroPaddedWeek => self.date.iso_week().week() as u64,
+            DateToken::Day | DateToken::ZeroPaddedDay => self.date.day() as u64,
+        };
+        (value(self.tokens[0]), value(self.tokens[1]))
+    }
+}
+
+impl VersionScheme for CalVer {
+    // Calendar versions have no scope: the date decides
+    fn increase(&self, last: &SemanticVersion, _scope: &str) -> SemanticVersion {
+        let (major, minor) = self.date_parts();
+        let mut next = SemanticVersion::default();
+        if (major, minor) > (last.major, last.minor) {
+            next.major = major;
+            next.minor = minor;
+        } else {
+            // Same period, or a date before the last version
+            next.major = last.major;
+            next.minor = last.minor;
+            next.patch = last.patch + 1;
+        }
+        next
+    }
+
+    fn core(&self, version: &SemanticVersion) -> String {
+        let part = |token: DateToken, value: u64| match token {
+            DateToken::ZeroPaddedYear
+            | DateToken::ZeroPaddedMonth
+            | DateToken::ZeroPaddedWeek
+            | DateToken::ZeroPaddedDay => format!("{:02}", value),
+            _ => value.to_string(),
+        };
+        format!(
+            "{}.{}.{}",
+            part(self.tokens[0], version.major),
+            part(self.tokens[1], version.minor),
+            version.patch
+        )
+    }
+}
+
+/// Date of a git time: seconds since the epoch in the given offset
+pub(crate) fn date_of(seconds: i64, offset_minutes: i32) -> NaiveDate {
+    DateTime::from_timestamp(seconds + offset_minutes as i64 * 60, 0)
+        .unwrap_or_default()
+        .date_naive()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tag_template::{TagTemplate, DEFAULT_TAG_TEMPLATE};
+
+    fn calver(format: &str, date: &str) -> CalVer {
+        CalVer::new(format, NaiveDate::parse_from_str(date, "%Y-%m-%d").unwrap()).unwrap()
+    }
+
+    fn version(version: &str) -> SemanticVersion {
+        SemanticVersion::from_string(version.to_string()).unwrap()
+    }
+
+    fn tag_name(scheme: &dyn VersionScheme, version: &SemanticVersion) -> String {
+        TagTemplate::new(DEFAULT_TAG_TEMPLATE, "v")
+            .unwrap()
+            .render(version, scheme)
+    }
+
+    #[test]
+    fn calver_increase_and_format() {
+        let monthly = calver("YYYY.0M.MICRO", "2024-06-15");
+        let next = monthly.increase(&version("2024.05.3"), "minor");
+        assert_eq!(tag_name(&monthly, &next), "v2024.06.0");
+        let next = monthly.increase(&version("2024.06.0"), "minor");
+        assert_eq!(tag_name(&monthly, &next), "v2024.06.1");
+
+        let weekly = calver("YY.0W.MICRO", "2024-12-30");
+        let next = weekly.increase(&SemanticVersion::default(), "minor");
+        assert_eq!(tag_name(&weekly, &next), "v25.01.0");
+
+        let mut rc = version("24.1.0-rc.2");
+        rc.major = 2024;
+        assert_eq!(tag_name(&monthly, &rc), "v2024.01.0-rc.2");
+
+        assert!(CalVer::new("YYYY.MM", NaiveDate::default()).is_err());
+        assert!(CalVer::new("YYYY.MICRO.MM", NaiveDate::default()).is_err());
+    }
+}
@@ -0,0 +1,39 @@
+mod forge_releases;
+mod git_tags;
+mod oci_registry;
+
+use crate::default_error::DefaultError;
+pub(crate) use crate::version_sources::forge_releases::ForgeReleases;
+pub(crate) use crate::version_sources::git_tags::GitTags;
+pub(crate) use crate::version_sources::oci_registry::OciRegistry;
+use std::error::Error;
+
+pub(crate) const GIT: &str = "git";
+pub(crate) const FORGE: &str = "forge";
+pub(crate) const OCI: &str = "oci";
+
+/// Where existing versions are published: git tags, forge releases or container image tags.
+/// Their names are read with the tag template like git tags
+pub(crate) trait VersionSource {
+    fn name(&self) -> String;
+    fn tag_names(&self) -> Result<Vec<String>, Box<dyn Error>>;
+}
+
+/// Tag names of all `sources`, merged and without duplicates
+pub(crate) fn merged_tag_names(
+    sources: &[Box<dyn VersionSource>],
+) -> Result<Vec<String>, Box<dyn Error>> {
+    let mut tag_names = vec![];
+    for source in sources {
+        let source_tag_names = source.tag_names().map_err(|e| DefaultError {
+            message: format!("Failed to list the versions of {}", source.name()),
+            source: Some(e),
+        })?;
+        log::info!("{}: {} versions", source.name(), source_tag_names.len());
+        tag_names.extend(source_tag_names);
+    }
+
+    tag_names.sort();
+    tag_names.dedup();
+    Ok(tag_names)
+}
@@ -0,0 +1,17 @@
+use crate::forges::Forge;
+use crate::version_sources::VersionSource;
+use std::error::Error;
+
+pub(crate) struct ForgeReleases {
+    pub(crate) forge: Box<dyn Forge>,
+}
+
+impl VersionSource for ForgeReleases {
+    fn name(&self) -> String {
+        format!("{} releases", self.forge.name())
+    }
+
+    fn tag_names(&self) -> Result<Vec<String>, Box<dyn Error>> {
+        self.forge.release_tag_names()
+    }
+}
@@ -0,0 +1,27 @@
+use crate::git_service;
+use crate::version_sources::VersionSource;
+use std::error::Error;
+
+pub(crate) struct GitTags {
+    pub(crate) repo_path: String,
+    pub(crate) force_fetch_tags: bool,
+    pub(crate) remotes: Vec<String>,
+    pub(crate) git_username: String,
+    pub(crate) git_token: String,
+}
+
+impl VersionSource for GitTags {
+    fn name(&self) -> String {
+        "git tags".to_string()
+    }
+
+    fn tag_names(&self) -> Result<Vec<String>, Box<dyn Error>> {
+        Ok(git_service::tag_names(
+            &self.repo_path,
+            self.force_fetch_tags,
+            &self.remotes,
+            &self.git_username,
+            &self.git_token,
+        )?)
+    }
+}
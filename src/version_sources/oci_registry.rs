@@ -0,0 +1,71 @@
+use crate::default_error::DefaultError;
+use crate::http_service;
+use crate::version_sources::VersionSource;
+use reqwest::header::HeaderMap;
+use serde_json::Value;
+use std::error::Error;
+
+/// Tags of an image repository in an OCI distribution registry
+pub(crate) struct OciRegistry {
+    registry_url: String,
+    name: String,
+    token: Option<String>,
+}
+
+impl OciRegistry {
+    /// `repository` is `<registry>/<name>`, e.g. `registry.example.com/owner/app`, with an
+    /// optional `http://` or `https://` scheme. `token` is sent as a bearer token
+    pub(crate) fn new(repository: &str, token: Option<String>) -> Result<Self, Box<dyn Error>> {
+        let (scheme, reference) = match repository.split_once("://") {
+            Some((scheme, reference)) => (scheme, reference),
+            None => ("https", repository),
+        };
+        let (registry, name) = reference
+            .split_once('/')
+            .filter(|(registry, name)| !registry.is_empty() && !name.is_empty())
+            .ok_or_else(|| DefaultError {
+                message: format!(
+                    "Invalid OCI repository: {}. Expected <registry>/<name>",
+                    repository
+                ),
+                source: None,
+            })?;
+
+        Ok(OciRegistry {
+            registry_url: format!("{}://{}", scheme, registry),
+            name: name.trim_end_matches('/').to_string(),
+            token,
+        })
+    }
+
+    fn headers(&self) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert("Accept", "application/json".parse().unwrap());
+        if let Some(token) = &self.token {
+            headers.insert(
+                "Authorization",
+                format!("Bearer {}", token).parse().unwrap(),
+            );
+        }
+        headers
+    }
+}
+
+impl VersionSource for OciRegistry {
+    fn name(&self) -> String {
+        format!("{}/{}", self.registry_url, self.name)
+    }
+
+    fn tag_names(&self) -> Result<Vec<String>, Box<dyn Error>> {
+        let url = format!("{}/v2/{}/tags/list", self.registry_url, self.name);
+
+        let pages = http_service::get_pages(url, Some(self.headers()), None)?;
+        Ok(pages
+            .iter()
+            .filter_map(|page| page.get("tags").and_then(Value::as_array))
+            .flatten()
+            .filter_map(Value::as_str)
+            .map(str::to_string)
+            .collect())
+    }
+}
@@ -0,0 +1,152 @@
+mod common;
+
+use assert_cmd::prelude::*;
+use common::{git_in, init_repo, Repo};
+use predicates::prelude::*;
+use std::path::Path;
+use std::process::Command;
+use tempfile::TempDir;
+
+// `develop` with a breaking change before the tag and one after it
+const BREAKING: Repo = Repo {
+    branch: "develop",
+    message: "feat!: first api",
+    commits: &[
+        "fix: typo",
+        "feat(api): paginate users\n\nBREAKING CHANGE: users are paginated",
+    ],
+    ..Repo::DEFAULT
+};
+
+fn svci_in(dir: &Path) -> Command {
+    let mut cmd = common::svci_in(dir);
+    cmd.env_remove("SCOPE");
+    cmd.env_remove("BREAKING_CHANGES");
+    cmd
+}
+
+#[test]
+fn escalates_to_a_major_bump() {
+    let tmp = TempDir::new().unwrap();
+    init_repo(
+        tmp.path(),
+        &Repo {
+            tags: &["v1.2.3"],
+            ..BREAKING
+        },
+    );
+
+    svci_in(tmp.path())
+        .args(["version", "--breaking-changes", "escalate"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("UPCOMING_VERSION=v2.0.0-dev.1."))
+        .stderr(predicate::str::contains(
+            "1 breaking changes since v1.2.3 need a major bump, but the scope is minor. Bumping major",
+        ));
+
+    svci_in(tmp.path())
+        .args(["scope", "--breaking-changes", "escalate"])
+        .assert()
+        .success()
+        .stdout("major\n");
+}
+
+#[test]
+fn fails_or_warns_below_major() {
+    let tmp = TempDir::new().unwrap();
+    init_repo(
+        tmp.path(),
+        &Repo {
+            tags: &["v1.2.3"],
+            ..BREAKING
+        },
+    );
+
+    svci_in(tmp.path())
+        .args(["version", "--breaking-changes", "fail"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "1 breaking changes since v1.2.3 need a major bump, but the scope is minor:",
+        ))
+        .stderr(predicate::str::contains(" feat(api): paginate users"));
+
+    svci_in(tmp.path())
+        .env("BREAKING_CHANGES", "warn")
+        .arg("version")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("UPCOMING_VERSION=v1.3.0-dev.1."))
+        .stderr(predicate::str::contains("WARN"))
+        .stderr(predicate::str::contains("1 breaking changes"));
+
+    svci_in(tmp.path())
+        .args(["version", "--breaking-changes", "fail", "--scope", "major"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("UPCOMING_VERSION=v2.0.0-dev.1."));
+}
+
+#[test]
+fn breaking_changes_bump_minor_before_1_0_0() {
+    let tmp = TempDir::new().unwrap();
+    init_repo(
+        tmp.path(),
+        &Repo {
+            tags: &["v0.4.1"],
+            ..BREAKING
+        },
+    );
+
+    svci_in(tmp.path())
+        .args([
+            "version",
+            "--scope",
+            "patch",
+            "--breaking-changes",
+            "escalate",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("UPCOMING_VERSION=v0.5.0-dev.1."))
+        .stderr(predicate::str::contains(
+            "need a minor bump, but the scope is patch. Bumping minor",
+        ));
+
+    svci_in(tmp.path())
+        .args(["version", "--breaking-changes", "fail"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("UPCOMING_VERSION=v0.5.0-dev.1."));
+}
+
+#[test]
+fn guards_the_releases_of_main() {
+    let tmp = TempDir::new().unwrap();
+    init_repo(
+        tmp.path(),
+        &Repo {
+            tags: &["v1.2.3"],
+            ..BREAKING
+        },
+    );
+    git_in(tmp.path(), &["switch", "-q", "-c", "main"]);
+
+    // Fallback bump without a newer pre-release
+    svci_in(tmp.path())
+        .args(["version", "--breaking-changes", "escalate"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("UPCOMING_VERSION=v2.0.0\n"));
+
+    // Release of the last pre-release
+    git_in(tmp.path(), &["tag", "v1.3.0-rc.1"]);
+    svci_in(tmp.path())
+        .args(["version", "--breaking-changes", "fail"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "1 breaking changes since v1.2.3 need a major bump, but the scope is minor",
+        ));
+}
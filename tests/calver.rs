@@ -0,0 +1,104 @@
+mod common;
+
+use assert_cmd::prelude::*;
+use common::{git_in, init_repo, Repo};
+use predicates::prelude::*;
+use std::path::Path;
+use std::process::Command;
+use tempfile::TempDir;
+
+// Committed on 2024-06-20
+const JUNE_20: Repo = Repo {
+    committer_date: Some("2024-06-20T12:00:00+09:00"),
+    ..Repo::DEFAULT
+};
+
+fn svci_version(dir: &Path) -> Command {
+    let mut cmd = common::svci_in(dir);
+    cmd.env("VERSION_SCHEME", "calver");
+    cmd.env_remove("CALVER_FORMAT");
+    cmd.env_remove("VERSION_DATE");
+    cmd.arg("version");
+    cmd
+}
+
+#[test]
+fn main_releases_the_first_version_of_the_month() {
+    let tmp = TempDir::new().unwrap();
+    init_repo(
+        tmp.path(),
+        &Repo {
+            tags: &["2024.05.3"],
+            ..JUNE_20
+        },
+    );
+
+    svci_version(tmp.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("UPCOMING_VERSION=v2024.06.0\n"))
+        .stdout(predicate::str::contains("LAST_VERSION=v2024.05.3\n"));
+
+    svci_version(tmp.path())
+        .args(["--date", "2024-07-01"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("UPCOMING_VERSION=v2024.07.0\n"));
+}
+
+#[test]
+fn prereleases_increase_micro_within_the_month() {
+    let tmp = TempDir::new().unwrap();
+    init_repo(
+        tmp.path(),
+        &Repo {
+            branch: "develop",
+            tags: &["v2024.06.0"],
+            ..JUNE_20
+        },
+    );
+    let sha = git_in(tmp.path(), &["rev-parse", "--short=8", "HEAD"]);
+
+    svci_version(tmp.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(format!(
+            "UPCOMING_VERSION=v2024.06.1-dev.1.{}\n",
+            sha
+        )))
+        .stdout(predicate::str::contains("LAST_VERSION=v2024.06.0\n"));
+
+    git_in(tmp.path(), &["switch", "-q", "-c", "release/june"]);
+    git_in(tmp.path(), &["tag", "v2024.06.1-rc.1"]);
+    svci_version(tmp.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "UPCOMING_VERSION=v2024.06.1-rc.2\n",
+        ))
+        .stdout(predicate::str::contains("LAST_VERSION=v2024.06.1-rc.1\n"));
+}
+
+#[test]
+fn calver_format_is_configurable() {
+    let tmp = TempDir::new().unwrap();
+    init_repo(
+        tmp.path(),
+        &Repo {
+            tags: &["24.24.0"],
+            ..JUNE_20
+        },
+    );
+
+    svci_version(tmp.path())
+        .env("CALVER_FORMAT", "YY.0W.MICRO")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("UPCOMING_VERSION=v24.25.0\n"));
+
+    svci_version(tmp.path())
+        .env("CALVER_FORMAT", "YYYY.MM")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Invalid CalVer format: YYYY.MM"));
+}
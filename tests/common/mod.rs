@@ -0,0 +1,282 @@
+#![allow(dead_code)]
+
+use assert_cmd::prelude::*;
+use std::fs;
+use std::io::{Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use tempfile::TempDir;
+
+/// Runs git in `dir` and returns its trimmed stdout
+pub fn git_in(dir: &Path, args: &[&str]) -> String {
+    git_at(dir, args, None)
+}
+
+fn git_at(dir: &Path, args: &[&str], committer_date: Option<&str>) -> String {
+    let mut cmd = Command::new("git");
+    cmd.current_dir(dir).args(args);
+    if let Some(date) = committer_date {
+        cmd.env("GIT_COMMITTER_DATE", date);
+    }
+    let output = cmd.output().unwrap();
+    assert!(output.status.success(), "git {:?}", args);
+    String::from_utf8(output.stdout).unwrap().trim().to_string()
+}
+
+/// Empty commit with `message`
+pub fn commit(dir: &Path, message: &str) {
+    git_in(dir, &["commit", "-q", "--allow-empty", "-m", message]);
+}
+
+/// History made by `init_repo`: the initial commit adds README.md and gets the tags,
+/// then `files` and `commits` follow, on `then_branch` when set
+pub struct Repo<'a> {
+    pub branch: &'a str,
+    pub message: &'a str,
+    pub tags: &'a [&'a str],
+    pub annotated: bool,
+    pub then_branch: Option<&'a str>,
+    /// Each file is added by a `feat:` commit
+    pub files: &'a [&'a str],
+    /// Empty commits
+    pub commits: &'a [&'a str],
+    pub committer_date: Option<&'a str>,
+}
+
+impl Repo<'_> {
+    pub const DEFAULT: Repo<'static> = Repo {
+        branch: "main",
+        message: "chore: init",
+        tags: &[],
+        annotated: false,
+        then_branch: None,
+        files: &[],
+        commits: &[],
+        committer_date: None,
+    };
+}
+
+pub fn init_repo(dir: &Path, repo: &Repo) {
+    let git = |args: &[&str]| git_at(dir, args, repo.committer_date);
+    git(&["init", "-q", "-b", repo.branch]);
+    git(&["config", "user.name", "Test User"]);
+    git(&["config", "user.email", "test@example.com"]);
+    fs::write(dir.join("README.md"), "temp repo").unwrap();
+    git(&["add", "."]);
+    git(&["commit", "-q", "-m", repo.message]);
+    for tag in repo.tags {
+        match repo.annotated {
+            true => git(&["tag", "-a", tag, "-m", tag]),
+            false => git(&["tag", tag]),
+        };
+    }
+    if let Some(branch) = repo.then_branch {
+        git(&["switch", "-q", "-c", branch]);
+    }
+    for file in repo.files {
+        fs::write(dir.join(file), file).unwrap();
+        git(&["add", file]);
+        git(&["commit", "-q", "-m", &format!("feat: add {}", file)]);
+    }
+    for message in repo.commits {
+        git(&["commit", "-q", "--allow-empty", "-m", message]);
+    }
+}
+
+/// Repository on `branch` with a README commit, tagged with `tags`
+pub fn init_git_repo(dir: &Path, branch: &str, tags: &[&str]) {
+    init_repo(
+        dir,
+        &Repo {
+            branch,
+            tags,
+            ..Repo::DEFAULT
+        },
+    );
+}
+
+/// Clone of an upstream with `tags` on its initial commit, checked out on `branch`
+pub fn clone_with_tags(tmp: &Path, tags: &[&str], branch: &str) -> PathBuf {
+    let upstream = tmp.join("upstream");
+    fs::create_dir(&upstream).unwrap();
+    init_git_repo(&upstream, "main", tags);
+
+    git_in(tmp, &["clone", "-q", upstream.to_str().unwrap(), "clone"]);
+    let clone = tmp.join("clone");
+    git_in(&clone, &["switch", "-q", "-C", branch]);
+    clone
+}
+
+/// Bare repository added to `dir` as remote `name`. It is deleted when dropped
+pub fn add_bare_remote(dir: &Path, name: &str) -> TempDir {
+    let remote = TempDir::new().unwrap();
+    git_in(remote.path(), &["init", "-q", "--bare"]);
+    git_in(
+        dir,
+        &["remote", "add", name, remote.path().to_str().unwrap()],
+    );
+    remote
+}
+
+/// Bare origin of `dir` with `refs` pushed
+pub fn add_origin(dir: &Path, refs: &[&str]) -> TempDir {
+    let origin = add_bare_remote(dir, "origin");
+    if !refs.is_empty() {
+        git_in(dir, &[&["push", "-q", "origin"], refs].concat());
+    }
+    origin
+}
+
+/// svci on the plain git repository `dir`, without CI detection, tag fetching and forge calls
+pub fn svci_in(dir: &Path) -> Command {
+    let mut cmd = Command::cargo_bin("svci").expect("binary exists");
+    cmd.current_dir(dir);
+    cmd.env("ENVIRONMENT", "test");
+    cmd.env("GITHUB_ACTIONS", "false");
+    cmd.env("GITLAB_CI", "false");
+    cmd.env("CLONE_TARGET_PATH", dir.to_str().unwrap());
+    cmd.env("FORCE_FETCH_TAGS", "false");
+    cmd.env_remove("SVCI_BRANCH");
+    cmd
+}
+
+pub struct RecordedRequest {
+    pub method: String,
+    pub path: String,
+    pub headers: String,
+    pub body: String,
+}
+
+pub type Responder = fn(&RecordedRequest) -> (u16, String);
+
+/// Responder that also returns extra header lines, e.g. `Link: <...>; rel="next"`
+pub type HeaderResponder = fn(&RecordedRequest) -> (u16, Vec<String>, String);
+
+/// Minimal HTTP/1.1 server on a random local port. Every request is recorded and
+/// answered by `responder` with a JSON body.
+pub struct HttpStub {
+    pub url: String,
+    requests: Arc<Mutex<Vec<RecordedRequest>>>,
+}
+
+impl HttpStub {
+    pub fn start(responder: Responder) -> HttpStub {
+        HttpStub::start_with(Arc::new(move |request: &RecordedRequest| {
+            let (status, body) = responder(request);
+            (status, vec![], body)
+        }))
+    }
+
+    pub fn start_with_headers(responder: HeaderResponder) -> HttpStub {
+        HttpStub::start_with(Arc::new(responder))
+    }
+
+    fn start_with(responder: Arc<ResponderFn>) -> HttpStub {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Arc::new(Mutex::new(vec![]));
+
+        let recorded = requests.clone();
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                handle(stream, &*responder, &recorded);
+            }
+        });
+
+        HttpStub { url, requests }
+    }
+
+    pub fn requests(&self) -> Vec<(String, String, String)> {
+        self.requests
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|r| (r.method.clone(), r.path.clone(), r.body.clone()))
+            .collect()
+    }
+
+    pub fn headers_of(&self, path_prefix: &str) -> Option<String> {
+        self.requests
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|r| r.path.starts_with(path_prefix))
+            .map(|r| r.headers.clone())
+    }
+}
+
+type ResponderFn = dyn Fn(&RecordedRequest) -> (u16, Vec<String>, String) + Send + Sync;
+
+fn handle(mut stream: TcpStream, responder: &ResponderFn, recorded: &Mutex<Vec<RecordedRequest>>) {
+    let mut buffer = vec![];
+    let mut chunk = [0u8; 4096];
+
+    // headers
+    let header_end = loop {
+        let n = stream.read(&mut chunk).unwrap_or(0);
+        if n == 0 {
+            return;
+        }
+        buffer.extend_from_slice(&chunk[..n]);
+        if let Some(pos) = find(&buffer, b"\r\n\r\n") {
+            break pos + 4;
+        }
+    };
+    let head = String::from_utf8_lossy(&buffer[..header_end]).to_string();
+    let lower = head.to_lowercase();
+
+    // body
+    let content_length = lower
+        .lines()
+        .find_map(|line| line.strip_prefix("content-length:"))
+        .and_then(|value| value.trim().parse::<usize>().ok());
+    let chunked = lower.contains("transfer-encoding: chunked");
+    loop {
+        let received = buffer.len() - header_end;
+        let done = match content_length {
+            Some(length) => received >= length,
+            None => !chunked || find(&buffer[header_end..], b"0\r\n\r\n").is_some(),
+        };
+        if done {
+            break;
+        }
+        let n = stream.read(&mut chunk).unwrap_or(0);
+        if n == 0 {
+            break;
+        }
+        buffer.extend_from_slice(&chunk[..n]);
+    }
+
+    let mut request_line = head.lines().next().unwrap_or("").split(' ');
+    let request = RecordedRequest {
+        method: request_line.next().unwrap_or("").to_string(),
+        path: request_line.next().unwrap_or("").to_string(),
+        headers: head.clone(),
+        body: String::from_utf8_lossy(&buffer[header_end..]).to_string(),
+    };
+
+    let (status, headers, body) = responder(&request);
+    recorded.lock().unwrap().push(request);
+
+    let headers: String = headers
+        .iter()
+        .flat_map(|header| [header.as_str(), "\r\n"])
+        .collect();
+    let response = format!(
+        "HTTP/1.1 {} Stub\r\nContent-Type: application/json\r\n{}Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        headers,
+        body.len(),
+        body
+    );
+    stream.write_all(response.as_bytes()).ok();
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
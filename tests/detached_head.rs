@@ -0,0 +1,122 @@
+mod common;
+
+use assert_cmd::prelude::*;
+use common::{commit, git_in, init_repo, Repo};
+use predicates::prelude::*;
+use std::path::Path;
+use std::process::Command;
+use tempfile::TempDir;
+
+// main: v1.2.3, develop: 2 commits ahead of main
+const DEVELOP: Repo = Repo {
+    tags: &["v1.2.3"],
+    then_branch: Some("develop"),
+    commits: &["feat: first", "feat: second"],
+    ..Repo::DEFAULT
+};
+
+fn svci_version(dir: &Path) -> Command {
+    let mut cmd = common::svci_in(dir);
+    cmd.env_remove("BRANCH_PREFERENCE");
+    cmd.arg("version");
+    cmd
+}
+
+#[test]
+fn detached_head_at_branch_tip_uses_that_branch() {
+    let tmp = TempDir::new().unwrap();
+    init_repo(tmp.path(), &DEVELOP);
+    git_in(tmp.path(), &["checkout", "-q", "--detach", "develop"]);
+
+    svci_version(tmp.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("UPCOMING_VERSION=v1.3.0-dev.1"))
+        .stderr(predicate::str::contains(
+            "branch develop (detached HEAD: tip of develop is HEAD)",
+        ));
+}
+
+#[test]
+fn detached_head_uses_remote_branch_whose_tip_is_head() {
+    let upstream = TempDir::new().unwrap();
+    init_repo(upstream.path(), &DEVELOP);
+    git_in(upstream.path(), &["switch", "-q", "-c", "release/1.3.x"]);
+    let tmp = TempDir::new().unwrap();
+    git_in(
+        tmp.path(),
+        &["clone", "-q", upstream.path().to_str().unwrap(), "clone"],
+    );
+    let clone = tmp.path().join("clone");
+    git_in(
+        &clone,
+        &["checkout", "-q", "--detach", "origin/release/1.3.x"],
+    );
+    // Only the remote-tracking branch points at HEAD
+    git_in(&clone, &["branch", "-q", "-D", "release/1.3.x"]);
+
+    svci_version(&clone)
+        .env("BRANCH_PREFERENCE", "^release/.*$")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("UPCOMING_VERSION=v1.3.0-rc.1"))
+        .stderr(predicate::str::contains(
+            "branch release/1.3.x (detached HEAD: tip of origin/release/1.3.x is HEAD)",
+        ));
+}
+
+#[test]
+fn detached_head_inside_history_uses_preferred_containing_branch() {
+    let tmp = TempDir::new().unwrap();
+    init_repo(tmp.path(), &DEVELOP);
+    git_in(tmp.path(), &["switch", "-q", "-c", "feature/login"]);
+    commit(tmp.path(), "feat: login");
+    git_in(tmp.path(), &["checkout", "-q", "--detach", "develop~1"]);
+
+    // develop and feature/login both contain HEAD
+    svci_version(tmp.path())
+        .assert()
+        .success()
+        .stderr(predicate::str::contains(
+            "branch develop (detached HEAD: develop contains HEAD, 1 commits ahead)",
+        ));
+
+    svci_version(tmp.path())
+        .env("BRANCH_PREFERENCE", "^feature/.*$")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains(
+            "branch feature/login (detached HEAD: feature/login contains HEAD, 2 commits ahead)",
+        ));
+}
+
+#[test]
+fn explicit_branch_wins_over_detached_head_resolution() {
+    let tmp = TempDir::new().unwrap();
+    init_repo(tmp.path(), &DEVELOP);
+    git_in(tmp.path(), &["checkout", "-q", "--detach", "develop"]);
+
+    svci_version(tmp.path())
+        .args(["--branch", "main"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("UPCOMING_VERSION=v1.3.0"))
+        .stderr(predicate::str::contains(
+            "branch main (from --branch or SVCI_BRANCH)",
+        ));
+}
+
+#[test]
+fn detached_head_without_containing_branch_fails() {
+    let tmp = TempDir::new().unwrap();
+    init_repo(tmp.path(), &DEVELOP);
+    git_in(tmp.path(), &["checkout", "-q", "--detach", "develop"]);
+    commit(tmp.path(), "feat: orphaned");
+
+    svci_version(tmp.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "no branch contains it. Set --branch or SVCI_BRANCH",
+        ));
+}
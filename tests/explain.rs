@@ -0,0 +1,87 @@
+mod common;
+
+use assert_cmd::prelude::*;
+use common::{git_in, init_git_repo};
+use predicates::prelude::*;
+use serde_json::Value;
+use std::path::Path;
+use std::process::Command;
+use tempfile::TempDir;
+
+fn svci_explain(dir: &Path) -> Command {
+    let mut cmd = common::svci_in(dir);
+    cmd.env_remove("TAG_TEMPLATE");
+    cmd.env_remove("RELEASE_GUARDS");
+    cmd.arg("explain");
+    cmd
+}
+
+#[test]
+fn explain_traces_a_prerelease() {
+    let tmp = TempDir::new().unwrap();
+    init_git_repo(
+        tmp.path(),
+        "develop",
+        &["v1.2.3", "v1.3.0-dev.1.abcd1234", "latest"],
+    );
+    let sha = git_in(tmp.path(), &["rev-parse", "--short=8", "HEAD"]);
+
+    svci_explain(tmp.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "pipeline: Git Repo\nbranch: develop\n",
+        ))
+        .stdout(predicate::str::contains(
+            "tag: latest: rejected, does not match the tag template",
+        ))
+        .stdout(predicate::str::contains("tag: v1.2.3: official version\n"))
+        .stdout(predicate::str::contains("last official: v1.2.3\n"))
+        .stdout(predicate::str::contains(
+            "stage: dev, develop matches ^(develop|feature/.*)$\n",
+        ))
+        .stdout(predicate::str::contains("bump: minor 1.2.3 -> 1.3.0\n"))
+        .stdout(predicate::str::contains("bump: prerelease dev.2\n"))
+        .stdout(predicate::str::contains(
+            "last pre-release: v1.3.0-dev.1.abcd1234\n",
+        ))
+        .stdout(predicate::str::contains(format!(
+            "UPCOMING_VERSION=v1.3.0-dev.2.{}\n",
+            sha
+        )));
+}
+
+#[test]
+fn explain_prints_json_with_fallbacks_and_errors() {
+    let tmp = TempDir::new().unwrap();
+    init_git_repo(tmp.path(), "main", &["v1.2.3"]);
+
+    let output = svci_explain(tmp.path())
+        .args(["--format", "json"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let json: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["upcoming_version"], "v1.3.0");
+    assert_eq!(json["last_version"], "v1.2.3");
+    assert_eq!(json["error"], Value::Null);
+    let steps = json["steps"].as_array().unwrap();
+    assert!(steps.iter().any(|step| step["step"] == "fallback"
+        && step["detail"]
+            .as_str()
+            .unwrap()
+            .starts_with("No pre-release tags found")));
+
+    let output = svci_explain(tmp.path())
+        .args(["--format", "json"])
+        .env("RELEASE_GUARDS", "^main$:~1.2")
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    let json: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["upcoming_version"], Value::Null);
+    assert!(json["error"]
+        .as_str()
+        .unwrap()
+        .contains("does not allow v1.3.0"));
+}
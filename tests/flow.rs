@@ -0,0 +1,153 @@
+mod common;
+
+use assert_cmd::prelude::*;
+use common::{add_bare_remote, git_in, init_repo, Repo};
+use predicates::prelude::*;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use tempfile::TempDir;
+
+// main: v1.2.3, develop: one commit ahead of main, checked out
+const DEVELOP: Repo = Repo {
+    tags: &["v1.2.3"],
+    then_branch: Some("develop"),
+    files: &["feature.txt"],
+    ..Repo::DEFAULT
+};
+
+fn svci_flow(dir: &Path, args: &[&str]) -> Command {
+    let mut cmd = common::svci_in(dir);
+    for name in ["MAIN_BRANCH", "DEVELOP_BRANCH", "TAG_PREFIX", "REMOTES"] {
+        cmd.env_remove(name);
+    }
+    cmd.arg("flow");
+    cmd.args(args);
+    cmd
+}
+
+#[test]
+fn release_start_branches_from_develop() {
+    let tmp = TempDir::new().unwrap();
+    init_repo(tmp.path(), &DEVELOP);
+    git_in(tmp.path(), &["switch", "-q", "main"]);
+
+    svci_flow(tmp.path(), &["release", "start"])
+        .assert()
+        .success()
+        .stdout("release/1.3.x\n");
+
+    assert_eq!(
+        git_in(tmp.path(), &["rev-parse", "--abbrev-ref", "HEAD"]),
+        "release/1.3.x"
+    );
+    assert_eq!(
+        git_in(tmp.path(), &["rev-parse", "HEAD"]),
+        git_in(tmp.path(), &["rev-parse", "develop"])
+    );
+    assert!(tmp.path().join("feature.txt").exists());
+
+    svci_flow(tmp.path(), &["release", "start", "--major"])
+        .assert()
+        .success()
+        .stdout("release/2.x.x\n");
+}
+
+#[test]
+fn hotfix_start_branches_from_main_and_pushes() {
+    let tmp = TempDir::new().unwrap();
+    init_repo(tmp.path(), &DEVELOP);
+    let origin = add_bare_remote(tmp.path(), "origin");
+
+    svci_flow(tmp.path(), &["hotfix", "start", "--push"])
+        .assert()
+        .success()
+        .stdout("origin: pushed hotfix/1.2.4\nhotfix/1.2.4\n");
+
+    assert_eq!(
+        git_in(origin.path(), &["rev-parse", "hotfix/1.2.4"]),
+        git_in(tmp.path(), &["rev-parse", "main"])
+    );
+    assert!(!tmp.path().join("feature.txt").exists());
+
+    svci_flow(tmp.path(), &["hotfix", "start"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "Branch hotfix/1.2.4 already exists",
+        ));
+}
+
+#[test]
+fn release_finish_merges_tags_and_pushes() {
+    let tmp = TempDir::new().unwrap();
+    init_repo(tmp.path(), &DEVELOP);
+    let origin = add_bare_remote(tmp.path(), "origin");
+    git_in(tmp.path(), &["switch", "-q", "-c", "release/1.3.x"]);
+    git_in(
+        tmp.path(),
+        &["commit", "-q", "--allow-empty", "-m", "fix: rc"],
+    );
+
+    svci_flow(tmp.path(), &["release", "finish", "--push"])
+        .assert()
+        .success()
+        .stdout("origin: pushed main, develop, v1.3.0\nv1.3.0\n");
+
+    // The tag is the merge into main, which develop contains too
+    assert_eq!(
+        git_in(origin.path(), &["rev-parse", "v1.3.0^{commit}"]),
+        git_in(origin.path(), &["rev-parse", "main"])
+    );
+    assert_eq!(
+        git_in(origin.path(), &["log", "-1", "--format=%s", "main"]),
+        "Merge branch 'release/1.3.x' into main"
+    );
+    git_in(
+        tmp.path(),
+        &["merge-base", "--is-ancestor", "release/1.3.x", "develop"],
+    );
+    git_in(tmp.path(), &["show", "main:feature.txt"]);
+
+    svci_flow(tmp.path(), &["release", "finish"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Tag v1.3.0 already exists"));
+}
+
+#[test]
+fn finish_requires_a_flow_branch() {
+    let tmp = TempDir::new().unwrap();
+    init_repo(tmp.path(), &DEVELOP);
+
+    svci_flow(tmp.path(), &["hotfix", "finish"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "develop is not a hotfix branch, e.g. hotfix/1.2.3",
+        ));
+}
+
+#[test]
+fn finish_with_a_conflict_leaves_nothing_behind() {
+    let tmp = TempDir::new().unwrap();
+    init_repo(tmp.path(), &DEVELOP);
+    git_in(tmp.path(), &["switch", "-q", "-c", "release/1.3.x"]);
+    fs::write(tmp.path().join("README.md"), "release").unwrap();
+    git_in(tmp.path(), &["commit", "-q", "-am", "fix: release readme"]);
+    git_in(tmp.path(), &["switch", "-q", "develop"]);
+    fs::write(tmp.path().join("README.md"), "develop").unwrap();
+    git_in(tmp.path(), &["commit", "-q", "-am", "docs: develop readme"]);
+    git_in(tmp.path(), &["switch", "-q", "release/1.3.x"]);
+    let main = git_in(tmp.path(), &["rev-parse", "main"]);
+
+    svci_flow(tmp.path(), &["release", "finish"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "Merging release/1.3.x into develop has conflicts. Nothing was merged",
+        ));
+
+    assert_eq!(git_in(tmp.path(), &["rev-parse", "main"]), main);
+    assert_eq!(git_in(tmp.path(), &["tag", "-l", "v1.3.0"]), "");
+}
@@ -0,0 +1,160 @@
+mod common;
+
+use assert_cmd::prelude::*;
+use common::{git_in, init_git_repo, HttpStub, RecordedRequest};
+use predicates::prelude::*;
+use std::path::Path;
+use std::process::Command;
+use tempfile::TempDir;
+
+fn created(_request: &RecordedRequest) -> (u16, String) {
+    (201, r#"{"id":1}"#.to_string())
+}
+
+fn svci_release(dir: &Path) -> Command {
+    let mut cmd = common::svci_in(dir);
+    cmd.env_remove("ENVIRONMENT");
+    cmd.arg("release");
+    cmd
+}
+
+#[test]
+fn git_repo_creates_release_on_github_enterprise() {
+    let stub = HttpStub::start(created);
+    let tmp = TempDir::new().unwrap();
+    init_git_repo(tmp.path(), "main", &[]);
+    let head = git_in(tmp.path(), &["rev-parse", "HEAD"]);
+
+    svci_release(tmp.path())
+        .env("FORGE_TYPE", "github")
+        .env("FORGE_API_URL", format!("{}/api/v3", stub.url))
+        .env("FORGE_REPOSITORY", "owner/repo")
+        .env("FORGE_TOKEN_ENV", "GHE_TOKEN")
+        .env("GHE_TOKEN", "ghe-token")
+        .arg("v1.0.0")
+        .assert()
+        .success();
+
+    let requests = stub.requests();
+    assert_eq!(requests.len(), 1);
+    let (method, path, body) = &requests[0];
+    assert_eq!(method, "POST");
+    assert_eq!(path, "/api/v3/repos/owner/repo/releases");
+    assert!(body.contains(&format!(r#""target_commitish":"{}""#, head)));
+    let headers = stub.headers_of("/api/v3").unwrap();
+    assert!(headers.contains("authorization: Bearer ghe-token"));
+}
+
+#[test]
+fn git_repo_creates_release_on_self_hosted_gitlab() {
+    let stub = HttpStub::start(created);
+    let tmp = TempDir::new().unwrap();
+    init_git_repo(tmp.path(), "main", &[]);
+    let head = git_in(tmp.path(), &["rev-parse", "HEAD"]);
+
+    svci_release(tmp.path())
+        .env("FORGE_TYPE", "gitlab")
+        .env("FORGE_URL", &stub.url)
+        .env("FORGE_REPOSITORY", "group/project")
+        .env("FORGE_TOKEN_ENV", "GITLAB_TOKEN")
+        .env("GITLAB_TOKEN", "glpat-token")
+        .arg("v1.0.0")
+        .assert()
+        .success();
+
+    let requests = stub.requests();
+    assert_eq!(requests.len(), 1);
+    let (_, path, body) = &requests[0];
+    assert_eq!(path, "/api/v4/projects/group%2Fproject/releases");
+    assert!(body.contains(&format!(r#""ref":"{}""#, head)));
+    let headers = stub.headers_of("/api/v4").unwrap();
+    assert!(headers.contains("private-token: glpat-token"));
+}
+
+#[test]
+fn git_repo_without_forge_is_not_supported() {
+    let tmp = TempDir::new().unwrap();
+    init_git_repo(tmp.path(), "main", &[]);
+
+    svci_release(tmp.path())
+        .env_remove("FORGE_TYPE")
+        .arg("v1.0.0")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Not supported pipeline: Git Repo"));
+}
+
+#[test]
+fn git_repo_infers_forge_from_remote_url() {
+    let stub = HttpStub::start(created);
+    let tmp = TempDir::new().unwrap();
+    init_git_repo(tmp.path(), "main", &[]);
+    let host = stub
+        .url
+        .trim_start_matches("http://")
+        .split(':')
+        .next()
+        .unwrap();
+    let remote_url = format!("{}/group/project.git", stub.url);
+    git_in(tmp.path(), &["remote", "add", "origin", &remote_url]);
+
+    svci_release(tmp.path())
+        .env_remove("FORGE_TYPE")
+        .env("FORGE_HOSTS", format!("github.com=github,{}=gitlab", host))
+        .env("GITLAB_TOKEN", "glpat-token")
+        .arg("v1.0.0")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("release on GitLab"));
+
+    let paths: Vec<String> = stub.requests().into_iter().map(|r| r.1).collect();
+    assert_eq!(paths, vec!["/api/v4/projects/group%2Fproject/releases"]);
+    let headers = stub.headers_of("/api/v4").unwrap();
+    assert!(headers.contains("private-token: glpat-token"));
+}
+
+#[test]
+fn git_repo_uses_token_from_credential_helper() {
+    let stub = HttpStub::start(created);
+    let tmp = TempDir::new().unwrap();
+    init_git_repo(tmp.path(), "main", &[]);
+    let remote_url = format!("{}/owner/repo.git", stub.url);
+    git_in(tmp.path(), &["remote", "add", "origin", &remote_url]);
+    git_in(
+        tmp.path(),
+        &[
+            "config",
+            "credential.helper",
+            "!f() { echo username=user; echo password=helper-token; }; f",
+        ],
+    );
+
+    svci_release(tmp.path())
+        .env("FORGE_TYPE", "gitea")
+        .env_remove("GITEA_TOKEN")
+        .env_remove("GITHUB_TOKEN")
+        .env_remove("GIT_TOKEN")
+        .arg("v1.0.0")
+        .assert()
+        .success();
+
+    let headers = stub
+        .headers_of("/api/v1/repos/owner/repo/releases")
+        .unwrap();
+    assert!(headers.contains("authorization: token helper-token"));
+}
+
+#[test]
+fn invalid_forge_type_is_reported() {
+    let tmp = TempDir::new().unwrap();
+    init_git_repo(tmp.path(), "main", &[]);
+
+    svci_release(tmp.path())
+        .env("FORGE_TYPE", "bitbucket")
+        .arg("v1.0.0")
+        .assert()
+        .code(101)
+        .stderr(predicate::str::contains(
+            "Invalid FORGE_TYPE: bitbucket. Expected github, gitlab or gitea",
+        ));
+}
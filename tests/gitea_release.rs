@@ -0,0 +1,107 @@
+mod common;
+
+use assert_cmd::prelude::*;
+use common::{HttpStub, RecordedRequest};
+use std::fs;
+use std::process::Command;
+use tempfile::TempDir;
+
+fn gitea(request: &RecordedRequest) -> (u16, String) {
+    if request.path == "/api/v1/version" {
+        (200, r#"{"version":"1.21.0"}"#.to_string())
+    } else if request.path.contains("/assets") {
+        (201, r#"{"id":7,"name":"app.tar.gz"}"#.to_string())
+    } else {
+        (201, r#"{"id":42,"tag_name":"v1.0.0"}"#.to_string())
+    }
+}
+
+fn svci_release(server_url: &str) -> Command {
+    let mut cmd = Command::cargo_bin("svci").expect("binary exists");
+    cmd.env_remove("ENVIRONMENT");
+    cmd.env("GITLAB_CI", "false");
+    cmd.env("GITHUB_SERVER_URL", server_url);
+    cmd.env("GITHUB_REPOSITORY", "owner/repo");
+    cmd.env("GITHUB_SHA", "0123456789abcdef");
+    cmd.env("GITHUB_TOKEN", "test-token");
+    cmd.arg("release");
+    cmd
+}
+
+#[test]
+fn gitea_actions_creates_release_with_attachment() {
+    let stub = HttpStub::start(gitea);
+    let tmp = TempDir::new().unwrap();
+    let attachment = tmp.path().join("app.tar.gz");
+    fs::write(&attachment, "binary").unwrap();
+
+    svci_release(&format!("{}/", stub.url))
+        .env("GITEA_ACTIONS", "true")
+        .env("GITHUB_ACTIONS", "true")
+        .args(["v1.0.0", "--description", "notes"])
+        .args(["--attachment", attachment.to_str().unwrap()])
+        .assert()
+        .success();
+
+    let requests = stub.requests();
+    assert_eq!(requests.len(), 2);
+
+    let (method, path, body) = &requests[0];
+    assert_eq!(method, "POST");
+    assert_eq!(path, "/api/v1/repos/owner/repo/releases");
+    assert!(body.contains(r#""tag_name":"v1.0.0""#));
+    assert!(body.contains(r#""target_commitish":"0123456789abcdef""#));
+    let headers = stub
+        .headers_of("/api/v1/repos/owner/repo/releases")
+        .unwrap();
+    assert!(headers.contains("authorization: token test-token"));
+
+    let (method, path, body) = &requests[1];
+    assert_eq!(method, "POST");
+    assert_eq!(
+        path,
+        "/api/v1/repos/owner/repo/releases/42/assets?name=app.tar.gz"
+    );
+    assert!(body.contains(r#"name="attachment"; filename="app.tar.gz""#));
+}
+
+#[test]
+fn gitea_server_is_detected_from_github_server_url() {
+    let stub = HttpStub::start(gitea);
+
+    svci_release(&stub.url)
+        .env_remove("GITEA_ACTIONS")
+        .env("GITHUB_ACTIONS", "true")
+        .arg("v1.0.0")
+        .assert()
+        .success()
+        .stderr(predicates::str::contains("on Gitea Actions"));
+
+    let paths: Vec<String> = stub.requests().into_iter().map(|r| r.1).collect();
+    assert_eq!(
+        paths,
+        vec!["/api/v1/version", "/api/v1/repos/owner/repo/releases"]
+    );
+}
+
+#[test]
+fn prerelease_is_read_with_the_tag_template() {
+    let stub = HttpStub::start(gitea);
+
+    for (version, prerelease) in [("v1.2.3", false), ("v1.3.0-rc.1", true)] {
+        svci_release(&stub.url)
+            .env("GITEA_ACTIONS", "true")
+            .env("GITHUB_ACTIONS", "true")
+            .env(
+                "TAG_TEMPLATE",
+                "release-{major}.{minor}.{patch}{pre}{build}",
+            )
+            .arg(version)
+            .assert()
+            .success();
+
+        let requests = stub.requests();
+        let (_, _, body) = requests.last().unwrap();
+        assert!(body.contains(&format!(r#""prerelease":{}"#, prerelease)));
+    }
+}
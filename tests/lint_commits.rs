@@ -0,0 +1,141 @@
+mod common;
+
+use assert_cmd::prelude::*;
+use common::{commit, init_repo, Repo};
+use predicates::prelude::*;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use tempfile::TempDir;
+
+// An unconventional commit tagged v1.0.0
+const UNCONVENTIONAL: Repo = Repo {
+    message: "initial commit",
+    tags: &["v1.0.0"],
+    ..Repo::DEFAULT
+};
+
+fn svci_lint(dir: &Path) -> Command {
+    let mut cmd = common::svci_in(dir);
+    cmd.env_remove("COMMIT_TYPES");
+    cmd.env_remove("COMMIT_SCOPES");
+    cmd.env_remove("MAX_HEADER_LENGTH");
+    cmd.env_remove("REQUIRED_FOOTERS");
+    cmd.env_remove("REMOTES");
+    cmd.arg("lint-commits");
+    cmd
+}
+
+#[test]
+fn lints_the_commits_since_the_last_tag() {
+    let tmp = TempDir::new().unwrap();
+    init_repo(tmp.path(), &UNCONVENTIONAL);
+    commit(tmp.path(), "feat(api): add users");
+    commit(tmp.path(), "fix(ui): align buttons");
+    commit(tmp.path(), "update dependencies");
+
+    svci_lint(tmp.path())
+        .args(["--scope", "api,cli"])
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains("lint refs/tags/v1.0.0..HEAD"))
+        .stderr(predicate::str::contains(
+            " update dependencies\n  - header \"update dependencies\" is not <type>[(<scope>)][!]: <description>\n",
+        ))
+        .stderr(predicate::str::contains(
+            " fix(ui): align buttons\n  - scope ui is not one of api, cli\n",
+        ))
+        .stderr(predicate::str::contains(
+            "2 of 3 commits do not follow Conventional Commits",
+        ))
+        .stderr(predicate::str::contains("initial commit").not());
+}
+
+#[test]
+fn checks_types_header_length_and_footers() {
+    let tmp = TempDir::new().unwrap();
+    init_repo(tmp.path(), &UNCONVENTIONAL);
+    commit(tmp.path(), "wip: almost done");
+    commit(
+        tmp.path(),
+        "feat: add a feature\n\nSigned-off-by: Test User <test@example.com>",
+    );
+
+    svci_lint(tmp.path())
+        .args(["v1.0.0..HEAD", "--type", "feat,fix"])
+        .args(["--max-header-length", "16", "--required-footer", "Signed-off-by"])
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains(
+            " wip: almost done\n  - type wip is not one of feat, fix\n  - footer Signed-off-by is missing\n",
+        ))
+        .stderr(predicate::str::contains(
+            " feat: add a feature\n  - header is longer than 16 characters\n",
+        ));
+}
+
+#[test]
+fn passes_conventional_commits() {
+    let tmp = TempDir::new().unwrap();
+    init_repo(tmp.path(), &UNCONVENTIONAL);
+    commit(
+        tmp.path(),
+        "feat!: drop the v1 api\n\nBREAKING CHANGE: v1 is gone",
+    );
+    commit(tmp.path(), "fixup! feat!: drop the v1 api");
+
+    svci_lint(tmp.path())
+        .assert()
+        .success()
+        .stderr(predicate::str::contains(
+            "1 commits follow Conventional Commits",
+        ));
+}
+
+#[test]
+fn lints_a_message_file_in_a_commit_msg_hook() {
+    let tmp = TempDir::new().unwrap();
+    init_repo(tmp.path(), &UNCONVENTIONAL);
+    let message_file = tmp.path().join("COMMIT_EDITMSG");
+
+    fs::write(
+        &message_file,
+        "docs: describe linting\n# Please enter the commit message\n",
+    )
+    .unwrap();
+    svci_lint(tmp.path())
+        .args(["--message-file", message_file.to_str().unwrap()])
+        .assert()
+        .success();
+
+    fs::write(&message_file, "docs: describe linting\nmore details\n").unwrap();
+    svci_lint(tmp.path())
+        .args(["--message-file", message_file.to_str().unwrap()])
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains(
+            "docs: describe linting\n  - a blank line must follow the header\n",
+        ));
+}
+
+#[test]
+fn ignores_the_diff_below_the_scissors_line() {
+    let tmp = TempDir::new().unwrap();
+    init_repo(tmp.path(), &UNCONVENTIONAL);
+    let message_file = tmp.path().join("COMMIT_EDITMSG");
+
+    // `git commit --verbose` appends the diff below the scissors line
+    fs::write(
+        &message_file,
+        "docs: describe linting\n\nSigned-off-by: Test User <test@example.com>\n\
+         # ------------------------ >8 ------------------------\n\
+         # Do not modify or remove the line above.\n\
+         diff --git a/README.md b/README.md\n\n+more details\n",
+    )
+    .unwrap();
+    svci_lint(tmp.path())
+        .args(["--message-file", message_file.to_str().unwrap()])
+        .args(["--required-footer", "Signed-off-by"])
+        .assert()
+        .success();
+}
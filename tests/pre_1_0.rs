@@ -0,0 +1,122 @@
+mod common;
+
+use assert_cmd::prelude::*;
+use common::init_git_repo;
+use predicates::prelude::*;
+use std::path::Path;
+use std::process::Command;
+use tempfile::TempDir;
+
+fn svci_version(dir: &Path) -> Command {
+    let mut cmd = common::svci_in(dir);
+    cmd.env_remove("SCOPE");
+    cmd.env_remove("INITIAL_VERSION");
+    cmd.env_remove("PRE_1_0");
+    cmd.arg("version");
+    cmd
+}
+
+#[test]
+fn initial_version_without_official_tags() {
+    let tmp = TempDir::new().unwrap();
+    init_git_repo(tmp.path(), "main", &[]);
+    svci_version(tmp.path())
+        .env("INITIAL_VERSION", "1.0.0")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("UPCOMING_VERSION=v1.0.0\n"))
+        .stdout(predicate::str::contains("LAST_VERSION=v0.0.0\n"));
+
+    let tmp = TempDir::new().unwrap();
+    init_git_repo(tmp.path(), "develop", &[]);
+    svci_version(tmp.path())
+        .args(["--initial-version", "v2.0.0"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("UPCOMING_VERSION=v2.0.0-dev.1."));
+
+    svci_version(tmp.path())
+        .args(["--initial-version", "1.0.0-rc.1"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "Invalid initial version: 1.0.0-rc.1 is a pre-release",
+        ));
+}
+
+#[test]
+fn initial_version_is_ignored_after_the_first_release() {
+    let tmp = TempDir::new().unwrap();
+    init_git_repo(tmp.path(), "develop", &["v0.3.0"]);
+
+    svci_version(tmp.path())
+        .env("INITIAL_VERSION", "1.0.0")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("UPCOMING_VERSION=v0.4.0-dev.1."));
+}
+
+#[test]
+fn pre_1_0_bumps_one_scope_lower() {
+    let tmp = TempDir::new().unwrap();
+    init_git_repo(tmp.path(), "develop", &["v0.3.0"]);
+
+    svci_version(tmp.path())
+        .arg("--pre-1-0")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("UPCOMING_VERSION=v0.3.1-dev.1."));
+    svci_version(tmp.path())
+        .env("PRE_1_0", "true")
+        .args(["--scope", "major"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("UPCOMING_VERSION=v0.4.0-dev.1."));
+
+    // The fallback bump of main
+    let tmp = TempDir::new().unwrap();
+    init_git_repo(tmp.path(), "main", &["v0.3.0"]);
+    svci_version(tmp.path())
+        .arg("--pre-1-0")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("UPCOMING_VERSION=v0.3.1\n"));
+
+    let tmp = TempDir::new().unwrap();
+    init_git_repo(tmp.path(), "develop", &["v1.2.3"]);
+    svci_version(tmp.path())
+        .arg("--pre-1-0")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("UPCOMING_VERSION=v1.3.0-dev.1."));
+}
+
+#[test]
+fn graduate_to_1_0_0() {
+    let tmp = TempDir::new().unwrap();
+    init_git_repo(tmp.path(), "develop", &["v0.3.0"]);
+    svci_version(tmp.path())
+        .args(["--graduate", "--pre-1-0"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("UPCOMING_VERSION=v1.0.0-dev.1."));
+
+    let tmp = TempDir::new().unwrap();
+    init_git_repo(tmp.path(), "main", &["v0.3.0"]);
+    svci_version(tmp.path())
+        .arg("--graduate")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("UPCOMING_VERSION=v1.0.0\n"))
+        .stdout(predicate::str::contains("LAST_VERSION=v0.3.0\n"));
+
+    let tmp = TempDir::new().unwrap();
+    init_git_repo(tmp.path(), "main", &["v1.2.3"]);
+    svci_version(tmp.path())
+        .arg("--graduate")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "Cannot graduate: the last official version 1.2.3 is 1.0.0 or later",
+        ));
+}
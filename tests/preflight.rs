@@ -0,0 +1,177 @@
+mod common;
+
+use assert_cmd::prelude::*;
+use common::{add_origin, commit, git_in, init_git_repo, HttpStub, RecordedRequest};
+use predicates::prelude::*;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use tempfile::TempDir;
+
+fn svci_tag(dir: &Path, tag_name: &str) -> Command {
+    let mut cmd = common::svci_in(dir);
+    cmd.env_remove("REMOTES");
+    cmd.env_remove("RELEASE_CHECKS");
+    cmd.env_remove("ALLOWED_BRANCHES");
+    cmd.args(["tag", tag_name]);
+    cmd
+}
+
+#[test]
+fn every_failed_check_is_listed() {
+    let tmp = TempDir::new().unwrap();
+    init_git_repo(tmp.path(), "feature/x", &["v1.2.3"]);
+    let origin = add_origin(tmp.path(), &["feature/x"]);
+    git_in(origin.path(), &["tag", "v1.2.2", "feature/x"]);
+    git_in(
+        tmp.path(),
+        &["commit", "-q", "--allow-empty", "-m", "feat: x"],
+    );
+    fs::write(tmp.path().join("README.md"), "changed").unwrap();
+
+    svci_tag(tmp.path(), "v1.2.2")
+        .env("RELEASE_CHECKS", "tag,version,branch,clean,pushed")
+        .assert()
+        .code(3)
+        .stderr(predicate::str::contains("Pre-flight checks failed:"))
+        .stderr(predicate::str::contains(
+            "  - tag: v1.2.2 already exists on origin\n",
+        ))
+        .stderr(predicate::str::contains(
+            "  - version: v1.2.2 is not greater than the last official version 1.2.3\n",
+        ))
+        .stderr(predicate::str::contains(
+            "  - branch: feature/x does not match ^(main|master|release/.*|hotfix/.*)$\n",
+        ))
+        .stderr(predicate::str::contains(
+            "  - clean: the working tree has changes in README.md\n",
+        ))
+        .stderr(predicate::str::contains(
+            "is not pushed to origin/feature/x\n",
+        ));
+
+    assert_eq!(git_in(origin.path(), &["tag"]), "v1.2.2");
+    assert_eq!(git_in(tmp.path(), &["tag"]), "v1.2.3");
+}
+
+#[test]
+fn tag_is_pushed_when_checks_pass() {
+    let tmp = TempDir::new().unwrap();
+    init_git_repo(tmp.path(), "main", &["v1.2.3"]);
+    let origin = add_origin(tmp.path(), &["main"]);
+
+    svci_tag(tmp.path(), "v1.3.0")
+        .args(["--check", "tag,version,branch,clean,pushed"])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains(
+            "pre-flight checks passed: tag, version, branch, clean, pushed",
+        ))
+        .stdout(predicate::str::contains("origin: pushed v1.3.0"));
+
+    assert_eq!(git_in(origin.path(), &["tag"]), "v1.3.0");
+
+    svci_tag(tmp.path(), "v1.3.0")
+        .args(["--check", "tag"])
+        .assert()
+        .code(3)
+        .stderr(predicate::str::contains(
+            "  - tag: v1.3.0 already exists locally\n",
+        ));
+}
+
+fn failed_build(request: &RecordedRequest) -> (u16, String) {
+    if request.path.contains("/check-runs") {
+        let runs = r#"{"check_runs":[
+            {"name":"build","status":"completed","conclusion":"failure"},
+            {"name":"lint","status":"completed","conclusion":"success"},
+            {"name":"release","status":"in_progress","conclusion":null}
+        ]}"#;
+        (200, runs.to_string())
+    } else {
+        (200, r#"{"state":"success","statuses":[]}"#.to_string())
+    }
+}
+
+#[test]
+fn failed_forge_checks_of_head_block_the_tag() {
+    let stub = HttpStub::start(failed_build);
+    let tmp = TempDir::new().unwrap();
+    init_git_repo(tmp.path(), "main", &["v1.2.3"]);
+    let _origin = add_origin(tmp.path(), &["main"]);
+    let head = git_in(tmp.path(), &["rev-parse", "HEAD"]);
+
+    svci_tag(tmp.path(), "v1.3.0")
+        .env_remove("ENVIRONMENT")
+        .env("FORGE_TYPE", "github")
+        .env("FORGE_API_URL", &stub.url)
+        .env("FORGE_REPOSITORY", "owner/repo")
+        .env("FORGE_TOKEN_ENV", "GH_TEST_TOKEN")
+        .env("GH_TEST_TOKEN", "test-token")
+        .args(["--check", "ci"])
+        .assert()
+        .code(3)
+        .stderr(predicate::str::contains(
+            "  - ci: checks of HEAD failed: build\n",
+        ));
+
+    let requests = stub.requests();
+    assert_eq!(
+        requests[0].1,
+        format!("/repos/owner/repo/commits/{}/check-runs?per_page=100", head)
+    );
+    assert_eq!(
+        requests[1].1,
+        format!("/repos/owner/repo/commits/{}/status", head)
+    );
+}
+
+fn created(_request: &RecordedRequest) -> (u16, String) {
+    (201, r#"{"id":1}"#.to_string())
+}
+
+#[test]
+fn release_passes_the_checks_of_its_own_tag() {
+    let stub = HttpStub::start(created);
+    let tmp = TempDir::new().unwrap();
+    init_git_repo(tmp.path(), "main", &["v1.2.3"]);
+    let _origin = add_origin(tmp.path(), &["main"]);
+
+    svci_tag(tmp.path(), "v1.3.0")
+        .env("RELEASE_CHECKS", "tag,version")
+        .assert()
+        .success();
+
+    let release = |name: &str| {
+        let mut cmd = common::svci_in(tmp.path());
+        cmd.env_remove("ENVIRONMENT")
+            .env_remove("REMOTES")
+            .env("RELEASE_CHECKS", "tag,version")
+            .env("FORGE_TYPE", "github")
+            .env("FORGE_API_URL", &stub.url)
+            .env("FORGE_REPOSITORY", "owner/repo")
+            .env("FORGE_TOKEN_ENV", "GH_TEST_TOKEN")
+            .env("GH_TEST_TOKEN", "test-token")
+            .args(["release", name]);
+        cmd
+    };
+    release("v1.3.0")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains(
+            "pre-flight checks passed: tag, version",
+        ));
+    assert_eq!(stub.requests().len(), 1);
+
+    // No longer the tag of HEAD
+    commit(tmp.path(), "fix: after the release");
+    release("v1.3.0")
+        .assert()
+        .code(3)
+        .stderr(predicate::str::contains(
+            "  - tag: v1.3.0 already exists locally\n",
+        ))
+        .stderr(predicate::str::contains(
+            "  - version: v1.3.0 is not greater than the last official version 1.3.0\n",
+        ));
+}
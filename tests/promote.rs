@@ -0,0 +1,114 @@
+mod common;
+
+use assert_cmd::prelude::*;
+use common::{add_origin, git_in, init_repo, HttpStub, RecordedRequest, Repo};
+use predicates::prelude::*;
+use std::path::Path;
+use std::process::Command;
+use tempfile::TempDir;
+
+// release/1.4.x with v1.4.0-rc.3 one commit behind HEAD
+const CANDIDATE: Repo = Repo {
+    branch: "release/1.4.x",
+    tags: &["v1.3.0", "v1.4.0-rc.3"],
+    annotated: true,
+    commits: &["fix: after rc"],
+    ..Repo::DEFAULT
+};
+
+fn svci_promote(dir: &Path, tag: &str) -> Command {
+    let mut cmd = common::svci_in(dir);
+    cmd.env_remove("REMOTES");
+    cmd.env_remove("CREATE_RELEASE");
+    cmd.args(["promote", tag]);
+    cmd
+}
+
+#[test]
+fn promote_tags_the_prerelease_commit() {
+    let tmp = TempDir::new().unwrap();
+    init_repo(tmp.path(), &CANDIDATE);
+    let rc_commit = git_in(tmp.path(), &["rev-parse", "HEAD~"]);
+    let origin = add_origin(tmp.path(), &[]);
+
+    svci_promote(tmp.path(), "v1.4.0-rc.3")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("origin: pushed v1.4.0"));
+
+    assert_eq!(git_in(origin.path(), &["tag"]), "v1.4.0");
+    assert_eq!(
+        git_in(origin.path(), &["rev-parse", "v1.4.0^{commit}"]),
+        rc_commit
+    );
+}
+
+#[test]
+fn promote_refuses_stale_or_released_candidates() {
+    let tmp = TempDir::new().unwrap();
+    init_repo(tmp.path(), &CANDIDATE);
+    let origin = add_origin(tmp.path(), &[]);
+
+    svci_promote(tmp.path(), "v1.3.0")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "Cannot promote: Tag v1.3.0 is not a pre-release",
+        ));
+
+    git_in(tmp.path(), &["tag", "v1.4.0-rc.4"]);
+    svci_promote(tmp.path(), "v1.4.0-rc.3")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "Cannot promote: A newer pre-release v1.4.0-rc.4 exists",
+        ));
+
+    git_in(tmp.path(), &["tag", "v1.4.0"]);
+    svci_promote(tmp.path(), "v1.4.0-rc.4")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "Cannot promote: Tag v1.4.0 already exists",
+        ));
+
+    assert_eq!(git_in(origin.path(), &["tag"]), "");
+}
+
+fn rc_release(request: &RecordedRequest) -> (u16, String) {
+    match request.method.as_str() {
+        "GET" => (200, r#"{"id":7,"body":"Notes of rc.3"}"#.to_string()),
+        _ => (201, r#"{"id":8}"#.to_string()),
+    }
+}
+
+#[test]
+fn promote_copies_release_notes_of_the_prerelease() {
+    let stub = HttpStub::start(rc_release);
+    let tmp = TempDir::new().unwrap();
+    init_repo(tmp.path(), &CANDIDATE);
+    let rc_commit = git_in(tmp.path(), &["rev-parse", "HEAD~"]);
+    let _origin = add_origin(tmp.path(), &[]);
+
+    svci_promote(tmp.path(), "v1.4.0-rc.3")
+        .env_remove("ENVIRONMENT")
+        .env("FORGE_TYPE", "github")
+        .env("FORGE_API_URL", &stub.url)
+        .env("FORGE_REPOSITORY", "owner/repo")
+        .env("FORGE_TOKEN_ENV", "GH_TEST_TOKEN")
+        .env("GH_TEST_TOKEN", "test-token")
+        .arg("--create-release")
+        .assert()
+        .success();
+
+    let requests = stub.requests();
+    assert_eq!(requests.len(), 2);
+    assert_eq!(requests[0].0, "GET");
+    assert_eq!(requests[0].1, "/repos/owner/repo/releases/tags/v1.4.0-rc.3");
+    let (method, path, body) = &requests[1];
+    assert_eq!(method, "POST");
+    assert_eq!(path, "/repos/owner/repo/releases");
+    assert!(body.contains(r#""tag_name":"v1.4.0""#));
+    assert!(body.contains(r#""body":"Notes of rc.3""#));
+    assert!(body.contains(&format!(r#""target_commitish":"{}""#, rc_commit)));
+}
@@ -0,0 +1,144 @@
+mod common;
+
+use assert_cmd::prelude::*;
+use common::{add_origin, git_in, init_git_repo, HttpStub, RecordedRequest};
+use predicates::prelude::*;
+use std::path::Path;
+use std::process::Command;
+use tempfile::TempDir;
+
+// Release candidates of the released v1.2.0 and of v1.3.0
+const CANDIDATES: &[&str] = &[
+    "v1.2.0-rc.1",
+    "v1.2.0-rc.2",
+    "v1.2.0",
+    "v1.3.0-rc.1",
+    "v1.3.0-rc.2",
+    "v1.3.0-rc.3",
+];
+
+fn svci_prune(dir: &Path) -> Command {
+    let mut cmd = common::svci_in(dir);
+    cmd.env_remove("REMOTES");
+    cmd.env_remove("PRUNE_KEEP");
+    cmd.env_remove("PRUNE_RELEASED");
+    cmd.env_remove("PRUNE_MIN_AGE_DAYS");
+    cmd.env_remove("PRUNE_DELETE_RELEASES");
+    cmd.arg("prune");
+    cmd
+}
+
+// Only v1.3.0-rc.1 has a release
+fn releases(request: &RecordedRequest) -> (u16, String) {
+    match request.path.ends_with("/v1.3.0-rc.1") {
+        true => (204, "".to_string()),
+        false => (404, r#"{"message":"Not Found"}"#.to_string()),
+    }
+}
+
+#[test]
+fn dry_run_lists_the_tags_without_deleting() {
+    let tmp = TempDir::new().unwrap();
+    init_git_repo(tmp.path(), "main", CANDIDATES);
+    let _origin = add_origin(tmp.path(), &["--tags", "main"]);
+
+    svci_prune(tmp.path())
+        .args(["--released", "--keep", "1", "--dry-run"])
+        .assert()
+        .success()
+        .stdout(
+            "would delete v1.2.0-rc.1\nwould delete v1.2.0-rc.2\n\
+             would delete v1.3.0-rc.1\nwould delete v1.3.0-rc.2\n",
+        );
+
+    assert_eq!(git_in(tmp.path(), &["tag", "-l"]).lines().count(), 6);
+}
+
+#[test]
+fn deletes_tags_locally_and_on_the_remote() {
+    let tmp = TempDir::new().unwrap();
+    init_git_repo(tmp.path(), "main", CANDIDATES);
+    let origin = add_origin(tmp.path(), &["--tags", "main"]);
+
+    svci_prune(tmp.path())
+        .args(["--keep", "2"])
+        .assert()
+        .success()
+        .stdout("v1.3.0-rc.1\n")
+        .stderr(predicate::str::contains("origin: deleted 1 tags"));
+
+    let remaining = "v1.2.0\nv1.2.0-rc.1\nv1.2.0-rc.2\nv1.3.0-rc.2\nv1.3.0-rc.3";
+    assert_eq!(git_in(tmp.path(), &["tag", "-l"]), remaining);
+    assert_eq!(git_in(origin.path(), &["tag", "-l"]), remaining);
+}
+
+#[test]
+fn skips_recent_tags() {
+    let tmp = TempDir::new().unwrap();
+    init_git_repo(tmp.path(), "main", CANDIDATES);
+    let _origin = add_origin(tmp.path(), &["--tags", "main"]);
+
+    svci_prune(tmp.path())
+        .args(["--released", "--min-age-days", "7"])
+        .assert()
+        .success()
+        .stdout("")
+        .stderr(predicate::str::contains("nothing to prune"));
+
+    assert_eq!(git_in(tmp.path(), &["tag", "-l"]).lines().count(), 6);
+}
+
+#[test]
+fn deletes_the_releases_of_pruned_tags() {
+    let stub = HttpStub::start(releases);
+    let tmp = TempDir::new().unwrap();
+    init_git_repo(tmp.path(), "main", CANDIDATES);
+    let _origin = add_origin(tmp.path(), &["--tags", "main"]);
+
+    svci_prune(tmp.path())
+        .env_remove("ENVIRONMENT")
+        .env("FORGE_TYPE", "gitea")
+        .env("FORGE_URL", &stub.url)
+        .env("FORGE_REPOSITORY", "owner/repo")
+        .env("GITEA_TOKEN", "gitea-token")
+        .env("PRUNE_DELETE_RELEASES", "true")
+        .args(["--keep", "1"])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("deleted release v1.3.0-rc.1"))
+        .stderr(predicate::str::contains("deleted release v1.3.0-rc.2").not());
+
+    let requests: Vec<(String, String)> = stub
+        .requests()
+        .into_iter()
+        .map(|(method, path, _)| (method, path))
+        .collect();
+    assert_eq!(
+        requests,
+        ["v1.2.0-rc.1", "v1.3.0-rc.1", "v1.3.0-rc.2"]
+            .iter()
+            .map(|tag_name| (
+                "DELETE".to_string(),
+                format!("/api/v1/repos/owner/repo/releases/tags/{}", tag_name)
+            ))
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(
+        git_in(tmp.path(), &["tag", "-l", "v1.3.0-rc.*"]),
+        "v1.3.0-rc.3"
+    );
+}
+
+#[test]
+fn needs_a_retention_policy() {
+    let tmp = TempDir::new().unwrap();
+    init_git_repo(tmp.path(), "main", CANDIDATES);
+    let _origin = add_origin(tmp.path(), &["--tags", "main"]);
+
+    svci_prune(tmp.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "Nothing to prune. Set --keep or --released",
+        ));
+}
@@ -0,0 +1,135 @@
+mod common;
+
+use assert_cmd::prelude::*;
+use common::clone_with_tags;
+use predicates::prelude::*;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use tempfile::TempDir;
+
+fn svci_in(dir: &Path, home: &Path) -> Command {
+    let mut cmd = common::svci_in(dir);
+    cmd.env("HOME", home);
+    cmd.env_remove("PULL_REQUEST_VERSIONS");
+    cmd
+}
+
+fn github_pull_request(dir: &Path, home: &Path) -> Command {
+    let mut cmd = svci_in(dir, home);
+    cmd.env("GITHUB_ACTIONS", "true");
+    cmd.env("GITLAB_CI", "false");
+    cmd.env_remove("GITHUB_SERVER_URL");
+    cmd.env_remove("GITHUB_EVENT_PATH");
+    cmd.env("GITHUB_REF", "refs/pull/123/merge");
+    cmd.env("GITHUB_REF_NAME", "123/merge");
+    cmd.env("GITHUB_HEAD_REF", "feature/login");
+    cmd.env("GITHUB_BASE_REF", "develop");
+    cmd.env("GITHUB_SHA", "abcdef1234567890");
+    cmd.env("GITHUB_ACTOR", "octocat");
+    cmd.env("GITHUB_TOKEN", "test-token");
+    cmd
+}
+
+fn gitlab_merge_request(dir: &Path, home: &Path) -> Command {
+    let mut cmd = svci_in(dir, home);
+    cmd.env("GITHUB_ACTIONS", "false");
+    cmd.env("GITLAB_CI", "true");
+    cmd.env("CI_COMMIT_REF_NAME", "feature/login");
+    cmd.env("CI_COMMIT_SHA", "1234abcd5678ef90");
+    cmd.env("CI_COMMIT_SHORT_SHA", "1234abcd");
+    cmd.env("CI_PROJECT_URL", "https://gitlab.com/group/project");
+    cmd.env("CI_JOB_TOKEN", "test-token");
+    cmd.env("GITLAB_USER_EMAIL", "test@example.com");
+    cmd.env("CI_MERGE_REQUEST_IID", "45");
+    cmd.env("CI_MERGE_REQUEST_SOURCE_BRANCH_NAME", "feature/login");
+    cmd.env("CI_MERGE_REQUEST_TARGET_BRANCH_NAME", "main");
+    cmd
+}
+
+#[test]
+fn github_pull_request_is_versioned_against_target_branch() {
+    let tmp = TempDir::new().unwrap();
+    let clone = clone_with_tags(tmp.path(), &["v1.2.3"], "feature/login");
+
+    github_pull_request(&clone, tmp.path())
+        .arg("version")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "UPCOMING_VERSION=v1.3.0-pr.123.1.abcdef12",
+        ))
+        .stdout(predicate::str::contains("LAST_VERSION=v1.2.3"));
+}
+
+#[test]
+fn github_pull_request_scope_uses_source_branch() {
+    let tmp = TempDir::new().unwrap();
+    let clone = clone_with_tags(tmp.path(), &["v1.2.3"], "feature/login");
+
+    github_pull_request(&clone, tmp.path())
+        .arg("scope")
+        .assert()
+        .success()
+        .stdout("minor\n");
+}
+
+#[test]
+fn github_pull_request_target_reads_the_number_of_the_event() {
+    let tmp = TempDir::new().unwrap();
+    let clone = clone_with_tags(tmp.path(), &["v1.2.3"], "feature/login");
+    let event_path = tmp.path().join("event.json");
+    fs::write(&event_path, r#"{"pull_request":{"number":77}}"#).unwrap();
+
+    github_pull_request(&clone, tmp.path())
+        .env("GITHUB_REF", "refs/heads/develop")
+        .env("GITHUB_REF_NAME", "develop")
+        .env("GITHUB_EVENT_PATH", &event_path)
+        .arg("version")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "UPCOMING_VERSION=v1.3.0-pr.77.1.abcdef12",
+        ));
+
+    // Without the event, it is versioned as the base branch
+    github_pull_request(&clone, tmp.path())
+        .env("GITHUB_REF", "refs/heads/develop")
+        .env("GITHUB_REF_NAME", "develop")
+        .arg("scope")
+        .assert()
+        .success()
+        .stdout("minor\n")
+        .stderr(predicate::str::contains(
+            "No pull request number in GITHUB_REF refs/heads/develop or GITHUB_EVENT_PATH",
+        ));
+}
+
+#[test]
+fn gitlab_merge_request_into_main_uses_upcoming_release() {
+    let tmp = TempDir::new().unwrap();
+    let clone = clone_with_tags(tmp.path(), &["v1.2.3", "v1.4.0-rc.2"], "feature/login");
+
+    gitlab_merge_request(&clone, tmp.path())
+        .arg("version")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "UPCOMING_VERSION=v1.4.0-pr.45.1.1234abcd",
+        ))
+        .stdout(predicate::str::contains("LAST_VERSION=v1.2.3"));
+}
+
+#[test]
+fn pull_request_versions_can_be_disabled() {
+    let tmp = TempDir::new().unwrap();
+    let clone = clone_with_tags(tmp.path(), &["v1.2.3"], "feature/login");
+
+    github_pull_request(&clone, tmp.path())
+        .args(["version", "--pull-request-versions", "false"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "UPCOMING_VERSION=v1.3.0-dev.1.abcdef12",
+        ));
+}
@@ -0,0 +1,140 @@
+mod common;
+
+use assert_cmd::prelude::*;
+use common::init_git_repo;
+use predicates::prelude::*;
+use std::path::Path;
+use std::process::Command;
+use tempfile::TempDir;
+
+fn svci_in(dir: &Path, args: &[&str]) -> Command {
+    let mut cmd = common::svci_in(dir);
+    cmd.env_remove("SCOPE");
+    cmd.args(args);
+    cmd
+}
+
+const TAGS: &[&str] = &[
+    "v1.2.0",
+    "v1.10.0",
+    "v1.9.0-rc.1",
+    "v1.9.0",
+    "v1.11.0-rc.1",
+    "v1.11.0-rc.2",
+    "v1.11.0-dev.3.abcd1234",
+    "not-a-version",
+];
+
+#[test]
+fn list_sorts_by_semver_precedence() {
+    let tmp = TempDir::new().unwrap();
+    init_git_repo(tmp.path(), "main", TAGS);
+
+    svci_in(tmp.path(), &["list"]).assert().success().stdout(
+        "v1.2.0\nv1.9.0-rc.1\nv1.9.0\nv1.10.0\nv1.11.0-dev.3.abcd1234\nv1.11.0-rc.1\nv1.11.0-rc.2\n",
+    );
+}
+
+#[test]
+fn list_filters() {
+    let tmp = TempDir::new().unwrap();
+    init_git_repo(tmp.path(), "main", TAGS);
+
+    svci_in(tmp.path(), &["list", "--official-only"])
+        .assert()
+        .success()
+        .stdout("v1.2.0\nv1.9.0\nv1.10.0\n");
+
+    svci_in(tmp.path(), &["list", "--stage", "rc", "--since", "v1.9.0"])
+        .assert()
+        .success()
+        .stdout("v1.11.0-rc.1\nv1.11.0-rc.2\n");
+}
+
+#[test]
+fn current_is_latest_official_or_latest_of_branch_line() {
+    let tmp = TempDir::new().unwrap();
+    init_git_repo(tmp.path(), "release/1.11.x", TAGS);
+
+    svci_in(tmp.path(), &["current"])
+        .assert()
+        .success()
+        .stdout("v1.11.0-rc.2\n");
+
+    svci_in(tmp.path(), &["current", "--official-only"])
+        .assert()
+        .success()
+        .stdout("v1.10.0\n");
+
+    svci_in(tmp.path(), &["current", "--branch", "main"])
+        .assert()
+        .success()
+        .stdout("v1.10.0\n");
+}
+
+#[test]
+fn current_ignores_prereleases_of_other_version_lines() {
+    let tmp = TempDir::new().unwrap();
+    init_git_repo(
+        tmp.path(),
+        "release/1.11.x",
+        &["v1.10.0", "v1.11.0-rc.2", "v1.12.0-rc.1", "v2.0.0-rc.4"],
+    );
+
+    svci_in(tmp.path(), &["current"])
+        .assert()
+        .success()
+        .stdout("v1.11.0-rc.2\n");
+
+    svci_in(tmp.path(), &["current", "--scope", "major"])
+        .assert()
+        .success()
+        .stdout("v2.0.0-rc.4\n");
+}
+
+#[test]
+fn next_computes_hypothetical_versions() {
+    let tmp = TempDir::new().unwrap();
+    init_git_repo(tmp.path(), "main", TAGS);
+
+    svci_in(tmp.path(), &["next", "--scope", "patch"])
+        .assert()
+        .success()
+        .stdout("v1.10.1\n");
+
+    svci_in(tmp.path(), &["next", "--stage", "rc"])
+        .assert()
+        .success()
+        .stdout("v1.11.0-rc.3\n");
+
+    svci_in(
+        tmp.path(),
+        &["next", "--scope", "major", "--from", "v2.3.4-rc.1"],
+    )
+    .assert()
+    .success()
+    .stdout("v3.0.0\n");
+
+    svci_in(tmp.path(), &["next", "--scope", "release"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("invalid value 'release'"));
+}
+
+#[test]
+fn current_and_next_keep_the_tag_format() {
+    let tmp = TempDir::new().unwrap();
+    init_git_repo(tmp.path(), "main", &["2024.05.1", "2024.06.3"]);
+
+    svci_in(tmp.path(), &["current"])
+        .assert()
+        .success()
+        .stdout("2024.06.3\n");
+    svci_in(
+        tmp.path(),
+        &["next", "--scheme", "calver", "--date", "2024-06-20"],
+    )
+    .assert()
+    .success()
+    .stdout("v2024.06.4\n");
+}
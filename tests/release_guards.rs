@@ -0,0 +1,70 @@
+mod common;
+
+use assert_cmd::prelude::*;
+use common::{git_in, init_git_repo};
+use predicates::prelude::*;
+use std::path::Path;
+use std::process::Command;
+use tempfile::TempDir;
+
+const GUARDS: &str = r"^release/1\..*$:^1; ^hotfix/.*$:~1.4";
+
+fn svci_in(dir: &Path, args: &[&str]) -> Command {
+    let mut cmd = common::svci_in(dir);
+    cmd.env("RELEASE_GUARDS", GUARDS);
+    cmd.args(args);
+    cmd
+}
+
+#[test]
+fn version_within_release_guard() {
+    let tmp = TempDir::new().unwrap();
+    init_git_repo(tmp.path(), "release/1.x", &["v1.9.0"]);
+
+    svci_in(tmp.path(), &["version"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("UPCOMING_VERSION=v1.10.0-rc.1\n"));
+}
+
+#[test]
+fn version_outside_release_guard_fails() {
+    let tmp = TempDir::new().unwrap();
+    init_git_repo(tmp.path(), "release/1.x", &["v1.9.0"]);
+
+    svci_in(tmp.path(), &["version", "--scope", "major"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "Release guard of branch release/1.x (\"^release/1\\..*$\") does not allow v2.0.0-rc.1",
+        ));
+}
+
+#[test]
+fn tag_outside_release_guard_is_refused() {
+    let tmp = TempDir::new().unwrap();
+    init_git_repo(tmp.path(), "hotfix/login", &["v1.4.2"]);
+
+    svci_in(tmp.path(), &["tag", "v1.5.0"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "Release guard of branch hotfix/login (\"^hotfix/.*$\") does not allow v1.5.0",
+        ));
+
+    assert!(git_in(tmp.path(), &["tag", "--list", "v1.5.0"]).is_empty());
+}
+
+#[test]
+fn invalid_release_guard_is_reported() {
+    let tmp = TempDir::new().unwrap();
+    init_git_repo(tmp.path(), "release/1.x", &["v1.9.0"]);
+
+    svci_in(tmp.path(), &["version"])
+        .env("RELEASE_GUARDS", "^release/.*$:latest")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "Invalid RELEASE_GUARDS entry \"^release/.*$:latest\"",
+        ));
+}
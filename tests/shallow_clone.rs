@@ -0,0 +1,231 @@
+mod common;
+
+use assert_cmd::prelude::*;
+use common::{commit, git_in, init_git_repo};
+use predicates::prelude::*;
+use std::fs;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::thread;
+use tempfile::TempDir;
+
+/// Smart HTTP server for the repositories under `base_path`, bridging each request to
+/// `git http-backend`. libgit2 does not support shallow fetches over git:// and ssh.
+struct GitHttpServer {
+    url: String,
+}
+
+fn start_git_http_server(base_path: &Path) -> GitHttpServer {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let base_path = base_path.to_path_buf();
+
+    thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            let base_path = base_path.clone();
+            thread::spawn(move || serve_git_request(stream, &base_path));
+        }
+    });
+
+    GitHttpServer { url }
+}
+
+fn serve_git_request(mut stream: TcpStream, base_path: &Path) {
+    let mut reader = BufReader::new(stream.try_clone().unwrap());
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line).unwrap();
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or("").to_string();
+    let target = parts.next().unwrap_or("").to_string();
+    let (path, query) = target.split_once('?').unwrap_or((&target, ""));
+
+    let mut headers = vec![];
+    loop {
+        let mut line = String::new();
+        reader.read_line(&mut line).unwrap();
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            headers.push((name.trim().to_lowercase(), value.trim().to_string()));
+        }
+    }
+    let header = |name: &str| {
+        headers
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.clone())
+    };
+
+    let mut body = vec![];
+    if header("transfer-encoding").is_some_and(|value| value.contains("chunked")) {
+        loop {
+            let mut size = String::new();
+            reader.read_line(&mut size).unwrap();
+            let size = usize::from_str_radix(size.trim(), 16).unwrap();
+            let mut chunk = vec![0u8; size + 2];
+            reader.read_exact(&mut chunk).unwrap();
+            if size == 0 {
+                break;
+            }
+            body.extend_from_slice(&chunk[..size]);
+        }
+    } else if let Some(length) = header("content-length") {
+        body.resize(length.parse().unwrap(), 0);
+        reader.read_exact(&mut body).unwrap();
+    }
+
+    let mut backend = Command::new("git")
+        .arg("http-backend")
+        .env("GIT_PROJECT_ROOT", base_path)
+        .env("GIT_HTTP_EXPORT_ALL", "1")
+        .env("REQUEST_METHOD", &method)
+        .env("PATH_INFO", path)
+        .env("QUERY_STRING", query)
+        .env("CONTENT_TYPE", header("content-type").unwrap_or_default())
+        .env("CONTENT_LENGTH", body.len().to_string())
+        .env(
+            "HTTP_CONTENT_ENCODING",
+            header("content-encoding").unwrap_or_default(),
+        )
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .unwrap();
+    backend.stdin.take().unwrap().write_all(&body).unwrap();
+    let output = backend.wait_with_output().unwrap().stdout;
+
+    // CGI headers, then the body
+    let header_end = output
+        .windows(4)
+        .position(|window| window == b"\r\n\r\n")
+        .unwrap();
+    let cgi_headers = String::from_utf8_lossy(&output[..header_end]).to_string();
+    let content = &output[header_end + 4..];
+    let mut status = "200 OK".to_string();
+    let mut response = vec![];
+    for line in cgi_headers.lines() {
+        match line.strip_prefix("Status:") {
+            Some(value) => status = value.trim().to_string(),
+            None => response.push(line.to_string()),
+        }
+    }
+    let head = format!(
+        "HTTP/1.1 {}\r\n{}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        status,
+        response.join("\r\n"),
+        content.len()
+    );
+    stream.write_all(head.as_bytes()).ok();
+    stream.write_all(content).ok();
+}
+
+fn svci_version(dir: &Path) -> Command {
+    let mut cmd = common::svci_in(dir);
+    cmd.env("FORCE_FETCH_TAGS", "true");
+    cmd.env_remove("REMOTES");
+    cmd.arg("version");
+    cmd
+}
+
+// `depth 1` clone of an upstream served over smart HTTP
+fn shallow_clone(base: &Path, commits_after_tag: usize, allow_reachable_wants: bool) {
+    let upstream = base.join("upstream");
+    fs::create_dir(&upstream).unwrap();
+    init_git_repo(&upstream, "develop", &["v1.0.0"]);
+    for i in 0..commits_after_tag {
+        commit(&upstream, &format!("feat: {}", i));
+    }
+    if allow_reachable_wants {
+        git_in(
+            &upstream,
+            &["config", "uploadpack.allowReachableSHA1InWant", "true"],
+        );
+    }
+    let server = start_git_http_server(base);
+
+    let remote_url = format!("{}/upstream", server.url);
+    git_in(
+        base,
+        &["clone", "-q", "--depth", "1", &remote_url, "shallow"],
+    );
+}
+
+fn commit_count(dir: &Path) -> usize {
+    git_in(dir, &["rev-list", "--count", "HEAD"])
+        .parse()
+        .unwrap()
+}
+
+#[test]
+fn shallow_clone_is_deepened_until_version_tag_is_reachable() {
+    let base = TempDir::new().unwrap();
+    shallow_clone(base.path(), 60, true);
+    let shallow = base.path().join("shallow");
+
+    svci_version(&shallow)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("UPCOMING_VERSION=v1.1.0-dev.1"))
+        .stdout(predicate::str::contains("LAST_VERSION=v1.0.0"));
+
+    assert_eq!(commit_count(&shallow), 61);
+    git_in(&shallow, &["merge-base", "--is-ancestor", "v1.0.0", "HEAD"]);
+    git_in(&shallow, &["fsck", "--connectivity-only"]);
+}
+
+#[test]
+fn shallow_clone_is_deepened_up_to_max_fetch_depth() {
+    let base = TempDir::new().unwrap();
+    shallow_clone(base.path(), 130, true);
+    let shallow = base.path().join("shallow");
+
+    svci_version(&shallow)
+        .env("MAX_FETCH_DEPTH", "60")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("LAST_VERSION=v1.0.0"));
+
+    assert_eq!(commit_count(&shallow), 61);
+    assert_eq!(
+        git_in(&shallow, &["rev-parse", "--is-shallow-repository"]),
+        "true"
+    );
+}
+
+#[test]
+fn shallow_clone_is_unshallowed_when_server_refuses_object_ids() {
+    let base = TempDir::new().unwrap();
+    shallow_clone(base.path(), 60, false);
+    let shallow = base.path().join("shallow");
+
+    svci_version(&shallow)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("LAST_VERSION=v1.0.0"));
+
+    assert_eq!(commit_count(&shallow), 61);
+    assert_eq!(
+        git_in(&shallow, &["rev-parse", "--is-shallow-repository"]),
+        "false"
+    );
+}
+
+#[test]
+fn shallow_clone_is_left_alone_when_deepening_is_disabled() {
+    let base = TempDir::new().unwrap();
+    shallow_clone(base.path(), 3, true);
+    let shallow = base.path().join("shallow");
+
+    svci_version(&shallow)
+        .env("MAX_FETCH_DEPTH", "0")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("LAST_VERSION=v1.0.0"));
+
+    assert_eq!(commit_count(&shallow), 1);
+}
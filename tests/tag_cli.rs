@@ -0,0 +1,51 @@
+mod common;
+
+use assert_cmd::prelude::*;
+use common::{add_bare_remote, git_in, init_git_repo};
+use predicates::prelude::*;
+use std::path::Path;
+use std::process::Command;
+use tempfile::TempDir;
+
+fn svci_tag(dir: &Path) -> Command {
+    let mut cmd = common::svci_in(dir);
+    cmd.env_remove("REMOTES");
+    cmd.arg("tag");
+    cmd
+}
+
+#[test]
+fn tag_is_pushed_to_every_remote() {
+    let tmp = TempDir::new().unwrap();
+    init_git_repo(tmp.path(), "main", &[]);
+    let origin = add_bare_remote(tmp.path(), "origin");
+    let upstream = add_bare_remote(tmp.path(), "upstream");
+
+    svci_tag(tmp.path())
+        .args(["v1.0.0", "--remote", "origin", "--remote", "upstream"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("origin: pushed v1.0.0"))
+        .stdout(predicate::str::contains("upstream: pushed v1.0.0"));
+
+    assert_eq!(git_in(origin.path(), &["tag"]), "v1.0.0");
+    assert_eq!(git_in(upstream.path(), &["tag"]), "v1.0.0");
+}
+
+#[test]
+fn tag_push_failures_are_reported_per_remote() {
+    let tmp = TempDir::new().unwrap();
+    init_git_repo(tmp.path(), "main", &[]);
+    let upstream = add_bare_remote(tmp.path(), "upstream");
+
+    svci_tag(tmp.path())
+        .env("REMOTES", "upstream,mirror")
+        .arg("v1.0.0")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("upstream: pushed v1.0.0"))
+        .stdout(predicate::str::contains("mirror: failed to push v1.0.0"))
+        .stderr(predicate::str::contains("Failed to push tag to mirror"));
+
+    assert_eq!(git_in(upstream.path(), &["tag"]), "v1.0.0");
+}
@@ -0,0 +1,114 @@
+mod common;
+
+use assert_cmd::prelude::*;
+use common::clone_with_tags;
+use predicates::prelude::*;
+use std::path::Path;
+use std::process::Command;
+use tempfile::TempDir;
+
+fn github_tag_pipeline(dir: &Path, home: &Path, tag: &str) -> Command {
+    let mut cmd = github_tag_env(dir, home, tag);
+    cmd.arg("version");
+    cmd
+}
+
+fn github_tag_env(dir: &Path, home: &Path, tag: &str) -> Command {
+    let mut cmd = common::svci_in(dir);
+    cmd.env("HOME", home);
+    cmd.env("GITHUB_ACTIONS", "true");
+    cmd.env_remove("GITHUB_SERVER_URL");
+    cmd.env_remove("GITHUB_HEAD_REF");
+    cmd.env_remove("TAG_PREFIX");
+    cmd.env("GITHUB_REF_TYPE", "tag");
+    cmd.env("GITHUB_REF", format!("refs/tags/{}", tag));
+    cmd.env("GITHUB_REF_NAME", tag);
+    cmd.env("GITHUB_SHA", "abcdef1234567890");
+    cmd.env("GITHUB_ACTOR", "octocat");
+    cmd.env("GITHUB_TOKEN", "test-token");
+    cmd
+}
+
+#[test]
+fn official_tag_is_the_upcoming_version() {
+    let tmp = TempDir::new().unwrap();
+    let clone = clone_with_tags(tmp.path(), &["v1.2.3", "v1.3.0-rc.1", "v1.3.0"], "main");
+
+    github_tag_pipeline(&clone, tmp.path(), "v1.3.0")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("UPCOMING_VERSION=v1.3.0\n"))
+        .stdout(predicate::str::contains("LAST_VERSION=v1.2.3\n"));
+}
+
+#[test]
+fn gitlab_prerelease_tag_follows_previous_prerelease() {
+    let tmp = TempDir::new().unwrap();
+    let clone = clone_with_tags(
+        tmp.path(),
+        &["v1.2.3", "v1.3.0-rc.1", "v1.3.0-rc.2"],
+        "main",
+    );
+
+    common::svci_in(&clone)
+        .env("GITLAB_CI", "true")
+        .env_remove("CI_MERGE_REQUEST_IID")
+        .env_remove("TAG_PREFIX")
+        .env("CI_COMMIT_TAG", "v1.3.0-rc.2")
+        .env("CI_COMMIT_REF_NAME", "v1.3.0-rc.2")
+        .env("CI_COMMIT_SHA", "1234abcd5678ef90")
+        .env("CI_COMMIT_SHORT_SHA", "1234abcd")
+        .env("CI_PROJECT_URL", "https://gitlab.com/group/project")
+        .env("CI_JOB_TOKEN", "test-token")
+        .env("GITLAB_USER_EMAIL", "test@example.com")
+        .arg("version")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("UPCOMING_VERSION=v1.3.0-rc.2\n"))
+        .stdout(predicate::str::contains("LAST_VERSION=v1.3.0-rc.1\n"));
+}
+
+#[test]
+fn invalid_semver_tag_fails() {
+    let tmp = TempDir::new().unwrap();
+    let clone = clone_with_tags(tmp.path(), &["v1.2.3", "v1.3"], "main");
+
+    github_tag_pipeline(&clone, tmp.path(), "v1.3")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "Tag v1.3 is not a semantic version",
+        ));
+}
+
+#[test]
+fn tag_without_configured_prefix_fails() {
+    let tmp = TempDir::new().unwrap();
+    let clone = clone_with_tags(tmp.path(), &["v1.2.3", "1.3.0"], "main");
+
+    github_tag_pipeline(&clone, tmp.path(), "1.3.0")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "Tag 1.3.0 does not match the tag prefix \"v\"",
+        ));
+
+    github_tag_pipeline(&clone, tmp.path(), "1.3.0")
+        .args(["--tag-prefix", ""])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("UPCOMING_VERSION=1.3.0\n"))
+        .stdout(predicate::str::contains("LAST_VERSION=1.2.3\n"));
+}
+
+#[test]
+fn scope_of_a_tag_pipeline_is_release() {
+    let tmp = TempDir::new().unwrap();
+    let clone = clone_with_tags(tmp.path(), &["v1.2.3"], "main");
+
+    github_tag_env(&clone, tmp.path(), "v1.2.3")
+        .arg("scope")
+        .assert()
+        .success()
+        .stdout("release\n");
+}
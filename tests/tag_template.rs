@@ -0,0 +1,87 @@
+mod common;
+
+use assert_cmd::prelude::*;
+use common::{git_in, init_git_repo};
+use predicates::prelude::*;
+use std::path::Path;
+use std::process::Command;
+use tempfile::TempDir;
+
+fn svci(dir: &Path, args: &[&str]) -> Command {
+    let mut cmd = common::svci_in(dir);
+    cmd.env_remove("TAG_PREFIX");
+    cmd.env_remove("TAG_TEMPLATE");
+    cmd.args(args);
+    cmd
+}
+
+#[test]
+fn version_discovers_and_renders_templated_tags() {
+    let tmp = TempDir::new().unwrap();
+    init_git_repo(tmp.path(), "main", &["release-1.2.3", "v9.0.0"]);
+
+    svci(tmp.path(), &["version"])
+        .env(
+            "TAG_TEMPLATE",
+            "release-{major}.{minor}.{patch}{pre}{build}",
+        )
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("UPCOMING_VERSION=release-1.3.0\n"))
+        .stdout(predicate::str::contains("LAST_VERSION=release-1.2.3\n"));
+}
+
+#[test]
+fn templates_separate_the_tags_of_packages() {
+    let tmp = TempDir::new().unwrap();
+    init_git_repo(
+        tmp.path(),
+        "develop",
+        &["app@1.2.3", "app@1.3.0-dev.1.abcd1234", "lib@2.0.0"],
+    );
+    let sha = git_in(tmp.path(), &["rev-parse", "--short=8", "HEAD"]);
+
+    svci(
+        tmp.path(),
+        &[
+            "version",
+            "--tag-template",
+            "app@{major}.{minor}.{patch}{pre}{build}",
+        ],
+    )
+    .assert()
+    .success()
+    .stdout(predicate::str::contains(format!(
+        "UPCOMING_VERSION=app@1.3.0-dev.2.{}\n",
+        sha
+    )))
+    .stdout(predicate::str::contains(
+        "LAST_VERSION=app@1.3.0-dev.1.abcd1234\n",
+    ));
+
+    svci(tmp.path(), &["list"])
+        .env("TAG_TEMPLATE", "lib@{major}.{minor}.{patch}{pre}{build}")
+        .assert()
+        .success()
+        .stdout("lib@2.0.0\n");
+}
+
+#[test]
+fn templates_may_drop_the_prefix_and_add_suffixes() {
+    let tmp = TempDir::new().unwrap();
+    init_git_repo(tmp.path(), "main", &["1.2.3-final"]);
+
+    svci(tmp.path(), &["next"])
+        .env("TAG_TEMPLATE", "{major}.{minor}.{patch}{pre}-final")
+        .assert()
+        .success()
+        .stdout("1.3.0-final\n");
+
+    svci(tmp.path(), &["version"])
+        .env("TAG_TEMPLATE", "{major}.{minor}.{patch}-{sha}")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "Invalid tag template: {major}.{minor}.{patch}-{sha}. Unknown placeholder {sha}",
+        ));
+}
@@ -40,7 +40,7 @@ fn tag(dir: &Path, name: &str) {
     sh_in(dir, &["tag", name]);
 }
 
-fn run_svci_in(dir: &Path) -> assert_cmd::assert::Assert {
+fn svci_in(dir: &Path) -> Command {
     let mut cmd = Command::cargo_bin("svci").expect("binary exists");
     cmd.current_dir(dir);
     cmd.env("ENVIRONMENT", "test");
@@ -50,7 +50,11 @@ fn run_svci_in(dir: &Path) -> assert_cmd::assert::Assert {
     cmd.env("CLONE_TARGET_PATH", dir.to_str().unwrap());
     cmd.env("FORCE_FETCH_TAGS", "false");
     cmd.arg("version");
-    cmd.assert()
+    cmd
+}
+
+fn run_svci_in(dir: &Path) -> assert_cmd::assert::Assert {
+    svci_in(dir).assert()
 }
 
 #[test]
@@ -155,3 +159,64 @@ fn main_branch_with_no_tags() {
         .stdout(predicate::str::contains("UPCOMING_VERSION=v0.1.0"))
         .stdout(predicate::str::contains("LAST_VERSION=v0.0.0"));
 }
+
+#[test]
+fn force_fetch_tags_from_remote_without_git_token() {
+    let remote = TempDir::new().unwrap();
+    init_git_repo(remote.path(), "develop");
+    tag(remote.path(), "v2.0.0");
+
+    let tmp = TempDir::new().unwrap();
+    init_git_repo(tmp.path(), "develop");
+    sh_in(
+        tmp.path(),
+        &["remote", "add", "origin", remote.path().to_str().unwrap()],
+    );
+
+    svci_in(tmp.path())
+        .env_remove("GIT_TOKEN")
+        .env("FORCE_FETCH_TAGS", "true")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("UPCOMING_VERSION=v2.1.0-dev.1"))
+        .stdout(predicate::str::contains("LAST_VERSION=v2.0.0"));
+}
+
+#[test]
+fn dev_numbering_by_commits_since_last_official_tag() {
+    let tmp = TempDir::new().unwrap();
+    init_git_repo(tmp.path(), "develop");
+
+    svci_in(tmp.path())
+        .args(["--dev-numbering", "commits"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("UPCOMING_VERSION=v0.1.0-dev.1."));
+
+    tag(tmp.path(), "v1.2.3");
+    tag(tmp.path(), "v1.3.0-dev.7");
+    sh_in(
+        tmp.path(),
+        &["commit", "-q", "--allow-empty", "-m", "feat: a"],
+    );
+    sh_in(
+        tmp.path(),
+        &["commit", "-q", "--allow-empty", "-m", "feat: b"],
+    );
+
+    svci_in(tmp.path())
+        .env("DEV_NUMBERING", "commits")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("UPCOMING_VERSION=v1.3.0-dev.8."))
+        .stdout(predicate::str::contains("LAST_VERSION=v1.3.0-dev.7"));
+
+    // rc branches keep the tag-based counter
+    sh_in(tmp.path(), &["switch", "-q", "-c", "release/1.3.x"]);
+    tag(tmp.path(), "v1.3.0-rc.1");
+    svci_in(tmp.path())
+        .env("DEV_NUMBERING", "commits")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("UPCOMING_VERSION=v1.3.0-rc.2"));
+}
@@ -0,0 +1,204 @@
+mod common;
+
+use assert_cmd::prelude::*;
+use common::{git_in, init_git_repo, HttpStub, RecordedRequest};
+use predicates::prelude::*;
+use std::path::Path;
+use std::process::Command;
+use tempfile::TempDir;
+
+fn svci_version(dir: &Path, stub: &HttpStub) -> Command {
+    let mut cmd = common::svci_in(dir);
+    cmd.env_remove("ENVIRONMENT");
+    cmd.env("FORGE_TYPE", "github");
+    cmd.env("FORGE_API_URL", &stub.url);
+    cmd.env("FORGE_REPOSITORY", "owner/repo");
+    cmd.env("FORGE_TOKEN_ENV", "GH_TEST_TOKEN");
+    cmd.env("GH_TEST_TOKEN", "test-token");
+    cmd.env_remove("VERSION_SOURCES");
+    cmd.env_remove("OCI_REPOSITORY");
+    cmd.env_remove("OCI_TOKEN");
+    cmd.arg("version");
+    cmd
+}
+
+fn registries(request: &RecordedRequest) -> (u16, String) {
+    if request.path.starts_with("/repos/owner/repo/releases") {
+        (
+            200,
+            r#"[{"tag_name":"v1.3.0-dev.4.abcd1234"},{"tag_name":"v1.2.3"}]"#.to_string(),
+        )
+    } else if request.path == "/v2/owner/app/tags/list" {
+        (
+            200,
+            r#"{"name":"owner/app","tags":["latest","v1.3.0-dev.7.abcd1234","1.2.3"]}"#.to_string(),
+        )
+    } else {
+        (404, r#"{"message":"Not Found"}"#.to_string())
+    }
+}
+
+#[test]
+fn forge_releases_seed_the_prerelease_counter() {
+    let stub = HttpStub::start(registries);
+    let tmp = TempDir::new().unwrap();
+    init_git_repo(tmp.path(), "develop", &["v1.2.3"]);
+    let sha = git_in(tmp.path(), &["rev-parse", "--short=8", "HEAD"]);
+
+    svci_version(tmp.path(), &stub)
+        .args(["--version-source", "git", "--version-source", "forge"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(format!(
+            "UPCOMING_VERSION=v1.3.0-dev.5.{}\n",
+            sha
+        )))
+        .stdout(predicate::str::contains(
+            "LAST_VERSION=v1.3.0-dev.4.abcd1234\n",
+        ));
+
+    let requests = stub.requests();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].1, "/repos/owner/repo/releases?per_page=100");
+}
+
+#[test]
+fn versions_of_all_sources_are_merged() {
+    let stub = HttpStub::start(registries);
+    let tmp = TempDir::new().unwrap();
+    init_git_repo(tmp.path(), "develop", &["v1.2.3"]);
+    let sha = git_in(tmp.path(), &["rev-parse", "--short=8", "HEAD"]);
+
+    svci_version(tmp.path(), &stub)
+        .env("VERSION_SOURCES", "git,forge,oci")
+        .env("OCI_REPOSITORY", format!("{}/owner/app", stub.url))
+        .env("OCI_TOKEN", "registry-token")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(format!(
+            "UPCOMING_VERSION=v1.3.0-dev.8.{}\n",
+            sha
+        )));
+
+    let headers = stub.headers_of("/v2/owner/app/tags/list").unwrap();
+    assert!(headers
+        .to_lowercase()
+        .contains("authorization: bearer registry-token"));
+}
+
+#[test]
+fn failing_sources_fail_the_version() {
+    let stub = HttpStub::start(registries);
+    let tmp = TempDir::new().unwrap();
+    init_git_repo(tmp.path(), "develop", &["v1.2.3"]);
+
+    svci_version(tmp.path(), &stub)
+        .args(["--version-source", "oci"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "The oci version source needs --oci-repository",
+        ));
+
+    svci_version(tmp.path(), &stub)
+        .args(["--version-source", "oci"])
+        .env("OCI_REPOSITORY", format!("{}/owner/missing", stub.url))
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(format!(
+            "Failed to list the versions of {}/owner/missing",
+            stub.url
+        )));
+}
+
+fn newer_forge_release(request: &RecordedRequest) -> (u16, String) {
+    match request.path.starts_with("/repos/owner/repo/releases") {
+        true => (
+            200,
+            r#"[{"tag_name":"v1.3.0"},{"tag_name":"v1.2.3"}]"#.to_string(),
+        ),
+        false => (404, r#"{"message":"Not Found"}"#.to_string()),
+    }
+}
+
+#[test]
+fn commits_are_not_counted_since_a_forge_only_release() {
+    let stub = HttpStub::start(newer_forge_release);
+    let tmp = TempDir::new().unwrap();
+    init_git_repo(tmp.path(), "develop", &["v1.2.3"]);
+    git_in(
+        tmp.path(),
+        &["commit", "-q", "--allow-empty", "-m", "feat!: drop v1"],
+    );
+    let sha = git_in(tmp.path(), &["rev-parse", "--short=8", "HEAD"]);
+
+    svci_version(tmp.path(), &stub)
+        .env("VERSION_SOURCES", "git,forge")
+        .args(["--dev-numbering", "commits", "--breaking-changes", "fail"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(format!(
+            "UPCOMING_VERSION=v1.4.0-dev.1.{}\n",
+            sha
+        )))
+        .stderr(predicate::str::contains(
+            "v1.3.0 is not a git tag, so the commits since it are unknown",
+        ));
+}
+
+// Two pages of releases and of image tags. The newest version is on the second page
+fn paginated(request: &RecordedRequest) -> (u16, Vec<String>, String) {
+    let next = |target: &str| vec![format!(r#"Link: <{}>; rel="next""#, target)];
+    match request.path.as_str() {
+        "/repos/owner/repo/releases?per_page=100" => (
+            200,
+            next("/repos/owner/repo/releases?per_page=100&page=2"),
+            r#"[{"tag_name":"v1.2.3"}]"#.to_string(),
+        ),
+        "/repos/owner/repo/releases?per_page=100&page=2" => (
+            200,
+            vec![],
+            r#"[{"tag_name":"v1.3.0-dev.4.abcd1234"}]"#.to_string(),
+        ),
+        "/v2/owner/app/tags/list" => (
+            200,
+            next("/v2/owner/app/tags/list?n=1&last=1.2.3"),
+            r#"{"name":"owner/app","tags":["1.2.3"]}"#.to_string(),
+        ),
+        "/v2/owner/app/tags/list?n=1&last=1.2.3" => (
+            200,
+            vec![],
+            r#"{"name":"owner/app","tags":["v1.3.0-dev.9.abcd1234"]}"#.to_string(),
+        ),
+        _ => (404, vec![], r#"{"message":"Not Found"}"#.to_string()),
+    }
+}
+
+#[test]
+fn every_page_of_the_sources_is_read() {
+    let stub = HttpStub::start_with_headers(paginated);
+    let tmp = TempDir::new().unwrap();
+    init_git_repo(tmp.path(), "develop", &["v1.2.3"]);
+    let sha = git_in(tmp.path(), &["rev-parse", "--short=8", "HEAD"]);
+
+    svci_version(tmp.path(), &stub)
+        .env("VERSION_SOURCES", "forge")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(format!(
+            "UPCOMING_VERSION=v1.3.0-dev.5.{}\n",
+            sha
+        )));
+
+    svci_version(tmp.path(), &stub)
+        .env("VERSION_SOURCES", "oci")
+        .env("OCI_REPOSITORY", format!("{}/owner/app", stub.url))
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(format!(
+            "UPCOMING_VERSION=v1.3.0-dev.10.{}\n",
+            sha
+        )));
+
+    assert_eq!(stub.requests().len(), 4);
+}
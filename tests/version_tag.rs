@@ -0,0 +1,190 @@
+mod common;
+
+use assert_cmd::prelude::*;
+use common::{add_origin, commit, git_in, init_git_repo};
+use predicates::prelude::*;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use tempfile::TempDir;
+
+fn svci_version(dir: &Path) -> Command {
+    let mut cmd = common::svci_in(dir);
+    cmd.env_remove("REMOTES");
+    cmd.env_remove("TAG_RETRIES");
+    cmd.args(["version", "--tag"]);
+    cmd
+}
+
+#[test]
+fn tags_head_without_pushing() {
+    let tmp = TempDir::new().unwrap();
+    init_git_repo(tmp.path(), "release/1.3.x", &["v1.2.3"]);
+    let origin = add_origin(tmp.path(), &["release/1.3.x", "v1.2.3"]);
+
+    svci_version(tmp.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("UPCOMING_VERSION=v1.3.0-rc.1"))
+        .stdout(predicate::str::contains("LAST_VERSION=v1.2.3"));
+
+    assert_eq!(
+        git_in(tmp.path(), &["tag", "--points-at", "HEAD"]),
+        "v1.2.3\nv1.3.0-rc.1"
+    );
+    assert_eq!(git_in(origin.path(), &["tag", "-l", "v1.3.0-*"]), "");
+}
+
+#[test]
+fn retries_when_another_pipeline_pushed_the_tag_first() {
+    let tmp = TempDir::new().unwrap();
+    init_git_repo(tmp.path(), "release/1.3.x", &["v1.2.3"]);
+    let origin = add_origin(tmp.path(), &["release/1.3.x", "v1.2.3"]);
+    git_in(tmp.path(), &["tag", "v1.3.0-rc.1"]);
+    git_in(tmp.path(), &["push", "-q", "origin", "v1.3.0-rc.1"]);
+    git_in(tmp.path(), &["tag", "-d", "v1.3.0-rc.1"]);
+    git_in(
+        tmp.path(),
+        &["commit", "-q", "--allow-empty", "-m", "feat: x"],
+    );
+
+    svci_version(tmp.path())
+        .arg("--push")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("UPCOMING_VERSION=v1.3.0-rc.2"))
+        .stderr(predicate::str::contains(
+            "origin: v1.3.0-rc.1 was pushed meanwhile. Retrying (1/3)",
+        ))
+        .stderr(predicate::str::contains("origin: pushed v1.3.0-rc.2"))
+        // The pipeline is detected once, not on every retry
+        .stderr(predicate::function(|stderr: &str| {
+            stderr.matches("on Git Repo").count() == 1
+        }));
+
+    assert_eq!(
+        git_in(origin.path(), &["tag", "-l", "v1.3.0-*"]),
+        "v1.3.0-rc.1\nv1.3.0-rc.2"
+    );
+    assert_eq!(
+        git_in(tmp.path(), &["tag", "--points-at", "HEAD"]),
+        "v1.3.0-rc.2"
+    );
+}
+
+#[test]
+fn gives_up_after_the_retries() {
+    let tmp = TempDir::new().unwrap();
+    init_git_repo(tmp.path(), "release/1.3.x", &["v1.2.3"]);
+    let origin = add_origin(tmp.path(), &["release/1.3.x", "v1.2.3"]);
+    git_in(tmp.path(), &["tag", "v1.3.0-rc.1"]);
+    git_in(tmp.path(), &["push", "-q", "origin", "v1.3.0-rc.1"]);
+    git_in(tmp.path(), &["tag", "-d", "v1.3.0-rc.1"]);
+
+    svci_version(tmp.path())
+        .args(["--push", "--tag-retries", "0"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Failed to push tag to origin"));
+
+    assert_eq!(git_in(tmp.path(), &["tag", "-l", "v1.3.0-*"]), "");
+    assert_eq!(
+        git_in(origin.path(), &["tag", "-l", "v1.3.0-*"]),
+        "v1.3.0-rc.1"
+    );
+}
+
+#[test]
+fn fails_when_the_official_version_was_released_meanwhile() {
+    let tmp = TempDir::new().unwrap();
+    init_git_repo(tmp.path(), "release/1.3.x", &["v1.2.3"]);
+    let origin = add_origin(tmp.path(), &["release/1.3.x", "v1.2.3"]);
+    git_in(tmp.path(), &["switch", "-q", "-c", "main"]);
+    git_in(tmp.path(), &["tag", "v1.3.0"]);
+    git_in(tmp.path(), &["push", "-q", "origin", "v1.3.0"]);
+    git_in(tmp.path(), &["tag", "-d", "v1.3.0"]);
+    git_in(
+        tmp.path(),
+        &["commit", "-q", "--allow-empty", "-m", "feat: x"],
+    );
+
+    svci_version(tmp.path())
+        .arg("--push")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "v1.3.0 is already released on origin",
+        ));
+
+    assert_eq!(git_in(tmp.path(), &["tag", "-l", "v1.*"]), "v1.2.3");
+    assert_eq!(
+        git_in(origin.path(), &["tag", "-l", "v1.*"]),
+        "v1.2.3\nv1.3.0"
+    );
+}
+
+#[test]
+fn runs_the_preflight_checks_before_tagging() {
+    let tmp = TempDir::new().unwrap();
+    init_git_repo(tmp.path(), "release/1.3.x", &["v1.2.3"]);
+    let _origin = add_origin(tmp.path(), &["release/1.3.x", "v1.2.3"]);
+    fs::write(tmp.path().join("README.md"), "changed").unwrap();
+
+    svci_version(tmp.path())
+        .args(["--push", "--check", "clean"])
+        .assert()
+        .code(3)
+        .stderr(predicate::str::contains("Pre-flight checks failed:"));
+
+    assert_eq!(git_in(tmp.path(), &["tag", "-l", "v1.3.0-*"]), "");
+}
+
+#[test]
+fn dev_numbers_of_other_commits_are_not_reused() {
+    let tmp = TempDir::new().unwrap();
+    let first = tmp.path().join("first");
+    fs::create_dir(&first).unwrap();
+    init_git_repo(&first, "develop", &["v1.2.3"]);
+    let origin = add_origin(&first, &["develop", "v1.2.3"]);
+    let origin_path = origin.path().to_str().unwrap();
+    git_in(
+        tmp.path(),
+        &["clone", "-q", "-b", "develop", origin_path, "second"],
+    );
+    let second = tmp.path().join("second");
+    git_in(&second, &["config", "user.name", "Test User"]);
+    git_in(&second, &["config", "user.email", "test@example.com"]);
+    commit(&first, "feat: first");
+    commit(&second, "feat: second");
+    let sha = |dir: &Path| git_in(dir, &["rev-parse", "--short=8", "HEAD"]);
+
+    svci_version(&first)
+        .arg("--push")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(format!(
+            "UPCOMING_VERSION=v1.3.0-dev.1.{}",
+            sha(&first)
+        )));
+
+    svci_version(&second)
+        .arg("--push")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(format!(
+            "UPCOMING_VERSION=v1.3.0-dev.2.{}",
+            sha(&second)
+        )))
+        .stderr(predicate::str::contains(
+            "origin: v1.3.0-dev.1 was pushed meanwhile. Retrying (1/3)",
+        ));
+
+    assert_eq!(
+        git_in(origin.path(), &["tag", "-l", "v1.3.0-*"]),
+        format!(
+            "v1.3.0-dev.1.{}\nv1.3.0-dev.2.{}",
+            sha(&first),
+            sha(&second)
+        )
+    );
+}
@@ -0,0 +1,125 @@
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+use std::process::Command;
+
+fn svci(args: &[&str]) -> Command {
+    let mut cmd = Command::cargo_bin("svci").expect("binary exists");
+    cmd.env("ENVIRONMENT", "test");
+    cmd.env_remove("TAG_PREFIX");
+    cmd.env_remove("PRERELEASE_STAGES");
+    cmd.args(args);
+    cmd
+}
+
+#[test]
+fn compare_prints_precedence() {
+    svci(&["compare", "v1.2.3", "v1.10.0"])
+        .assert()
+        .success()
+        .stdout("<\n");
+    svci(&["compare", "1.2.3-rc.1", "1.2.3-rc.1"])
+        .assert()
+        .success()
+        .stdout("=\n");
+    svci(&["compare", "v1.2.3", "v1.2.3-rc.9"])
+        .assert()
+        .success()
+        .stdout(">\n");
+}
+
+#[test]
+fn compare_exit_code() {
+    svci(&["compare", "--exit-code", "v1.2.3", "v1.2.3"])
+        .assert()
+        .code(0)
+        .stdout("");
+    svci(&[
+        "compare",
+        "--exit-code",
+        "v1.2.3-dev.1.abcd1234",
+        "v1.2.3-rc.1",
+    ])
+    .assert()
+    .code(1);
+    svci(&["compare", "--exit-code", "v2.0.0", "v1.9.9"])
+        .assert()
+        .code(2);
+}
+
+#[test]
+fn validate_accepts_versions_of_the_scheme() {
+    for version in [
+        "v1.2.3",
+        "v1.2.3-rc.1",
+        "v1.2.3-dev.4.abcd1234",
+        "v1.2.3-pr.12.1.abcd1234",
+    ] {
+        svci(&["validate", version])
+            .assert()
+            .success()
+            .stdout(format!("{}\n", version));
+    }
+
+    svci(&[
+        "validate",
+        "--tag-prefix",
+        "",
+        "--satisfies",
+        "^1.2",
+        "1.9.0",
+    ])
+    .assert()
+    .success();
+}
+
+#[test]
+fn validate_rejects_versions_outside_the_scheme() {
+    for (version, reason) in [
+        ("1.2.3", "missing the tag prefix \"v\""),
+        ("v1.2", "Invalid version string format"),
+        (
+            "v1.2.3-beta.1.abcd1234",
+            "pre-release stage beta is not one of dev, rc, pr",
+        ),
+        (
+            "v1.2.3-rc.1.abcd1234",
+            "rc versions must not have a commit SHA",
+        ),
+        ("v1.2.3-dev.1", "dev versions must end with a commit SHA"),
+        ("v1.2.3-dev.1.xyz", "commit SHA xyz is not hexadecimal"),
+        ("v01.2.3", "expected v1.2.3"),
+    ] {
+        svci(&["validate", version])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains(format!(
+                "{} is not a valid version: {}",
+                version, reason
+            )));
+    }
+
+    svci(&["validate", "--satisfies", "^1.2", "v2.0.0"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("does not satisfy ^1.2"));
+}
+
+#[test]
+fn satisfies_prints_matching_versions() {
+    svci(&[
+        "satisfies",
+        "1.x || >=2.1 <3",
+        "v1.4.0",
+        "v2.0.0",
+        "v2.3.0",
+        "v2.4.0-rc.1",
+    ])
+    .assert()
+    .success()
+    .stdout("v1.4.0\nv2.3.0\n");
+
+    svci(&["satisfies", "1.2.3 - 1.4", "v1.5.0"])
+        .assert()
+        .code(1)
+        .stdout("");
+}